@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS queues(
+    guild_id BIGINT PRIMARY KEY,
+    voice_channel_id BIGINT NOT NULL,
+    text_channel_id BIGINT NOT NULL,
+    position_ms BIGINT NOT NULL,
+    volume REAL NOT NULL,
+    looping BOOLEAN NOT NULL,
+    CONSTRAINT fk_guilds FOREIGN KEY(guild_id) REFERENCES guilds(guild_id) ON
+    DELETE
+        CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS queued_tracks(
+    guild_id BIGINT NOT NULL,
+    position BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    PRIMARY KEY (guild_id, position),
+    CONSTRAINT fk_queues FOREIGN KEY(guild_id) REFERENCES queues(guild_id) ON
+    DELETE
+        CASCADE
+);
\ No newline at end of file
@@ -0,0 +1,9 @@
+ALTER TABLE queues
+    ADD COLUMN repeat_mode SMALLINT NOT NULL DEFAULT 0,
+    ADD COLUMN repeat_times INT NOT NULL DEFAULT 0;
+
+UPDATE queues
+SET repeat_mode = 1
+WHERE looping;
+
+ALTER TABLE queues DROP COLUMN looping;
\ No newline at end of file
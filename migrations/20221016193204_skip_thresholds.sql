@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS skip_thresholds(
+    guild_id BIGINT PRIMARY KEY,
+    threshold INT NOT NULL,
+    is_percentage BOOLEAN NOT NULL,
+    CONSTRAINT fk_guilds FOREIGN KEY(guild_id) REFERENCES guilds(guild_id) ON
+    DELETE
+        CASCADE
+);
\ No newline at end of file
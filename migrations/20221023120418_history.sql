@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS history(
+    id BIGSERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    title TEXT,
+    url TEXT,
+    duration_ms BIGINT,
+    requester_id BIGINT,
+    CONSTRAINT fk_guilds FOREIGN KEY(guild_id) REFERENCES guilds(guild_id) ON
+    DELETE
+        CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS history_guild_id_idx ON history(guild_id, id);
\ No newline at end of file
@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS playlists(
+    playlist_id BIGSERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    owner_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    shared BOOLEAN NOT NULL DEFAULT FALSE,
+    UNIQUE (guild_id, owner_id, name),
+    CONSTRAINT fk_guilds FOREIGN KEY(guild_id) REFERENCES guilds(guild_id) ON
+    DELETE
+        CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS playlist_tracks(
+    playlist_id BIGINT NOT NULL,
+    position INT NOT NULL,
+    name TEXT NOT NULL,
+    title TEXT,
+    url TEXT,
+    duration_ms BIGINT,
+    PRIMARY KEY (playlist_id, position),
+    CONSTRAINT fk_playlists FOREIGN KEY(playlist_id) REFERENCES playlists(playlist_id) ON
+    DELETE
+        CASCADE
+);
\ No newline at end of file
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS favourites(
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    title TEXT,
+    url TEXT,
+    duration_ms BIGINT,
+    UNIQUE (user_id, name)
+);
\ No newline at end of file
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS queue_settings(
+    guild_id BIGINT PRIMARY KEY,
+    fair_queue BOOLEAN NOT NULL DEFAULT FALSE,
+    CONSTRAINT fk_guilds FOREIGN KEY(guild_id) REFERENCES guilds(guild_id) ON
+    DELETE
+        CASCADE
+);
\ No newline at end of file
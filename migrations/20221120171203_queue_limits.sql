@@ -0,0 +1,6 @@
+ALTER TABLE queue_settings
+    ADD COLUMN max_user_tracks INT,
+    ADD COLUMN max_queue_length INT,
+    ADD COLUMN max_track_length_secs INT,
+    ADD COLUMN max_playlist_size INT,
+    ADD COLUMN exempt_djs BOOLEAN NOT NULL DEFAULT TRUE;
\ No newline at end of file
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS radio_stations(
+    id BIGSERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    UNIQUE (guild_id, name),
+    CONSTRAINT fk_guilds FOREIGN KEY(guild_id) REFERENCES guilds(guild_id) ON
+    DELETE
+        CASCADE
+);
\ No newline at end of file
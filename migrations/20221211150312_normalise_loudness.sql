@@ -0,0 +1,2 @@
+ALTER TABLE queue_settings
+    ADD COLUMN normalise BOOLEAN NOT NULL DEFAULT FALSE;
\ No newline at end of file
@@ -0,0 +1,2 @@
+ALTER TABLE queue_settings
+    ADD COLUMN crossfade_secs INT NOT NULL DEFAULT 0;
\ No newline at end of file
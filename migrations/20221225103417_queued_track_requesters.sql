@@ -0,0 +1,2 @@
+ALTER TABLE queued_tracks
+    ADD COLUMN requester_id BIGINT;
\ No newline at end of file
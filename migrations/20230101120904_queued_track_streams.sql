@@ -0,0 +1,5 @@
+ALTER TABLE queued_tracks
+    ADD COLUMN title TEXT,
+    ADD COLUMN live BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN radio BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN stream_title TEXT;
\ No newline at end of file
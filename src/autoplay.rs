@@ -0,0 +1,83 @@
+use std::{collections::HashSet, time::Duration};
+
+use rand::seq::SliceRandom;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    playlists::{get_related_tracks, get_youtube_video_id},
+    queue::QueuedTrack,
+};
+
+/// How many tracks of the youtube mix are looked at, the first ones are the most related
+const RELATED_TRACKS: usize = 15;
+/// Tracks from the history are only picked again once this many others were played after them
+const RECENT_TRACKS: usize = 10;
+
+/// Identifies a track regardless of how its link was written, to spot tracks that were played already.
+fn track_key(url: Option<&str>, name: &str) -> String {
+    match url.and_then(get_youtube_video_id) {
+        Some(video_id) => video_id.to_string(),
+        None => url.unwrap_or(name).to_lowercase(),
+    }
+}
+
+/// Picks a track to play once the queue ran dry, from the youtube mix of the last played track if possible,
+/// otherwise one from the history that was not played recently. The history is the newest first.
+pub async fn find_autoplay_track(history: &[QueuedTrack]) -> Option<QueuedTrack> {
+    let last_track = history.first()?;
+
+    let played: HashSet<String> = history
+        .iter()
+        .map(|track| track_key(track.url.as_deref(), &track.name))
+        .collect();
+
+    if let Some(video_id) = last_track.url.as_deref().and_then(get_youtube_video_id) {
+        match get_related_tracks(video_id, RELATED_TRACKS).await {
+            Ok(related) => {
+                let related = related.into_iter().find(|track| {
+                    track
+                        .url
+                        .as_deref()
+                        .map_or(false, |url| !played.contains(&track_key(Some(url), url)))
+                });
+
+                if let Some(mut related) = related {
+                    let url = related.url.take().unwrap_or_default();
+
+                    return Some(QueuedTrack {
+                        title: Some(related.title),
+                        url: Some(url.clone()),
+                        duration: related.duration.map(Duration::from_secs_f64),
+                        uploader: related.uploader,
+                        // The last thumbnail yt-dlp lists is the largest one
+                        thumbnail: related.thumbnails.pop().map(|thumbnail| thumbnail.url),
+                        ..QueuedTrack::new(url, None)
+                    });
+                }
+            }
+            Err(e) => warn!("Could not get related tracks, {:?}", e),
+        }
+    }
+
+    let recent: HashSet<String> = history
+        .iter()
+        .take(RECENT_TRACKS)
+        .map(|track| track_key(track.url.as_deref(), &track.name))
+        .collect();
+
+    let candidates: Vec<&QueuedTrack> = history
+        .iter()
+        .skip(RECENT_TRACKS)
+        .filter(|track| !recent.contains(&track_key(track.url.as_deref(), &track.name)))
+        .collect();
+
+    candidates
+        .choose(&mut rand::thread_rng())
+        .map(|&track| QueuedTrack {
+            uuid: Uuid::new_v4(),
+            requester: None,
+            start_time: None,
+            ..track.clone()
+        })
+}
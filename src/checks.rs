@@ -21,12 +21,42 @@ async fn not_blacklisted(
     _: &mut Args,
     _: &CommandOptions,
 ) -> StdResult<(), Reason> {
-    let guild = msg.guild(ctx).await.unwrap();
-    if check_if_administrator(ctx, guild, msg.author.id).await {
+    check_not_blacklisted(ctx, msg.guild_id.unwrap(), msg.author.id).await
+}
+
+#[check]
+#[name = "dj_only"]
+async fn dj_only(
+    ctx: &Context,
+    msg: &Message,
+    _: &mut Args,
+    _: &CommandOptions,
+) -> StdResult<(), Reason> {
+    check_dj_only(ctx, msg.guild_id.unwrap(), msg.author.id).await
+}
+
+#[check]
+#[name = "admin_only"]
+async fn admin_only(
+    ctx: &Context,
+    msg: &Message,
+    _: &mut Args,
+    _: &CommandOptions,
+) -> StdResult<(), Reason> {
+    check_admin_only(ctx, msg.guild_id.unwrap(), msg.author.id).await
+}
+
+pub async fn check_not_blacklisted(
+    ctx: &Context,
+    guild_id: GuildId,
+    author: UserId,
+) -> StdResult<(), Reason> {
+    let guild = guild_id.to_guild_cached(ctx).await.unwrap();
+    if check_if_administrator(ctx, guild, author).await {
         Ok(())
     } else {
-        check_if_already_playing(ctx, msg).await?;
-        let perm_level = get_author_perm_level(ctx, msg).await?;
+        check_if_already_playing(ctx, guild_id, author).await?;
+        let perm_level = get_author_perm_level(ctx, guild_id, author).await?;
         if perm_level != UserPerm::Blacklisted {
             Ok(())
         } else {
@@ -35,22 +65,19 @@ async fn not_blacklisted(
     }
 }
 
-#[check]
-#[name = "dj_only"]
-async fn dj_only(
+pub async fn check_dj_only(
     ctx: &Context,
-    msg: &Message,
-    _: &mut Args,
-    _: &CommandOptions,
+    guild_id: GuildId,
+    author: UserId,
 ) -> StdResult<(), Reason> {
-    let guild = msg.guild(ctx).await.unwrap();
-    if check_if_administrator(ctx, guild, msg.author.id).await {
+    let guild = guild_id.to_guild_cached(ctx).await.unwrap();
+    if check_if_administrator(ctx, guild, author).await {
         Ok(())
     } else {
-        check_if_already_playing(ctx, msg).await?;
-        let perm_level = get_author_perm_level(ctx, msg).await?;
+        check_if_already_playing(ctx, guild_id, author).await?;
+        let perm_level = get_author_perm_level(ctx, guild_id, author).await?;
         if perm_level != UserPerm::Blacklisted {
-            if guild_has_dj_mode_enabled(ctx, msg).await? || perm_level >= UserPerm::Dj {
+            if guild_has_dj_mode_enabled(ctx, guild_id).await? || perm_level >= UserPerm::Dj {
                 Ok(())
             } else {
                 Err(Reason::User(INSUFFICIENT_PERMISSIONS_MESSAGE.to_string()))
@@ -61,20 +88,17 @@ async fn dj_only(
     }
 }
 
-#[check]
-#[name = "admin_only"]
-async fn admin_only(
+pub async fn check_admin_only(
     ctx: &Context,
-    msg: &Message,
-    _: &mut Args,
-    _: &CommandOptions,
+    guild_id: GuildId,
+    author: UserId,
 ) -> StdResult<(), Reason> {
-    let guild = msg.guild(ctx).await.unwrap();
+    let guild = guild_id.to_guild_cached(ctx).await.unwrap();
 
-    if check_if_administrator(ctx, guild, msg.author.id).await {
+    if check_if_administrator(ctx, guild, author).await {
         Ok(())
     } else {
-        let perm_level = get_author_perm_level(ctx, msg).await?;
+        let perm_level = get_author_perm_level(ctx, guild_id, author).await?;
         if perm_level == UserPerm::Admin {
             Ok(())
         } else {
@@ -83,17 +107,35 @@ async fn admin_only(
     }
 }
 
+/// Whether the user is an administrator of the guild, or was given the dj or admin perm.
+pub async fn is_dj_or_admin(ctx: &Context, guild_id: GuildId, author: UserId) -> bool {
+    let guild = guild_id.to_guild_cached(ctx).await.unwrap();
+
+    if check_if_administrator(ctx, guild, author).await {
+        return true;
+    }
+
+    matches!(
+        get_author_perm_level(ctx, guild_id, author).await,
+        Ok(perm_level) if perm_level >= UserPerm::Dj
+    )
+}
+
 async fn check_if_administrator(ctx: &Context, guild: Guild, author: UserId) -> bool {
     let perms = guild.member_permissions(ctx, author).await.unwrap();
     perms.administrator()
 }
 
-async fn check_if_already_playing(ctx: &Context, msg: &Message) -> StdResult<(), Reason> {
-    let guild = msg.guild(ctx).await.unwrap();
+async fn check_if_already_playing(
+    ctx: &Context,
+    guild_id: GuildId,
+    author: UserId,
+) -> StdResult<(), Reason> {
+    let guild = guild_id.to_guild_cached(ctx).await.unwrap();
 
     let author_channel_id = guild
         .voice_states
-        .get(&msg.author.id)
+        .get(&author)
         .and_then(|voice_state| voice_state.channel_id);
 
     let bot_channel_id = guild
@@ -114,10 +156,11 @@ async fn check_if_already_playing(ctx: &Context, msg: &Message) -> StdResult<(),
     Ok(())
 }
 
-async fn get_author_perm_level(ctx: &Context, msg: &Message) -> StdResult<UserPerm, Reason> {
-    let guild_id = msg.guild_id.unwrap();
-    let author_id = msg.author.id;
-
+async fn get_author_perm_level(
+    ctx: &Context,
+    guild_id: GuildId,
+    author_id: UserId,
+) -> StdResult<UserPerm, Reason> {
     let data = ctx.data.read().await;
     let pool = data.get::<PoolContainer>().unwrap();
 
@@ -132,10 +175,10 @@ async fn get_author_perm_level(ctx: &Context, msg: &Message) -> StdResult<UserPe
     }
 }
 
-async fn guild_has_dj_mode_enabled(ctx: &Context, msg: &Message) -> StdResult<bool, Reason> {
+async fn guild_has_dj_mode_enabled(ctx: &Context, guild_id: GuildId) -> StdResult<bool, Reason> {
     let data = ctx.data.read().await;
     let redis_con = data.get::<DjOnlyContainer>().unwrap().clone();
-    if !check_if_guild_in_store(redis_con, msg.guild_id.unwrap())
+    if !check_if_guild_in_store(redis_con, guild_id)
         .await
         .map_err(|e| Reason::Log(format!("{e:?}")))?
     {
@@ -0,0 +1,71 @@
+use serenity::{
+    framework::standard::{macros::command, CommandResult},
+    model::prelude::*,
+    prelude::*,
+};
+
+use crate::{
+    autoplay::find_autoplay_track,
+    checks::*,
+    data::PoolContainer,
+    db::{get_autoplay, set_autoplay},
+    history::load_history,
+    invoker::Invoker,
+    queue::QueueMap,
+};
+
+#[command]
+#[aliases("radio")]
+#[checks(dj_only)]
+#[description = "Enables/Disables autoplay, which adds related songs once the queue runs out"]
+#[bucket = "global"]
+async fn autoplay(ctx: &Context, msg: &Message) -> CommandResult {
+    toggle_autoplay(ctx, msg.into()).await
+}
+
+pub async fn toggle_autoplay(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let data = ctx.data.read().await;
+    let pool = data.get::<PoolContainer>().unwrap();
+
+    let autoplay = !get_autoplay(pool, guild_id.into()).await?;
+
+    set_autoplay(pool, guild_id.into(), autoplay).await?;
+
+    let queue_container = data.get::<QueueMap>().unwrap().clone();
+    let queue = queue_container.get(&guild_id).map(|queue| queue.clone());
+
+    if let Some(queue) = &queue {
+        queue.set_autoplay(autoplay);
+    }
+
+    if !autoplay {
+        invoker.say(ctx, "Disabled autoplay").await?;
+        return Ok(());
+    }
+
+    invoker
+        .say(
+            ctx,
+            "Enabled autoplay, related songs are added once the queue runs out",
+        )
+        .await?;
+
+    let manager = songbird::get(ctx).await.unwrap().clone();
+
+    if let (Some(queue), Some(handler_lock)) = (queue, manager.get(guild_id)) {
+        let history = load_history(pool, guild_id, &queue).await?;
+
+        // Nothing would end to trigger autoplay when the queue already ran out
+        if queue.is_empty() {
+            if let Some(track) = find_autoplay_track(&history).await {
+                queue
+                    .add(track, handler_lock, invoker.channel_id(), ctx.http.clone())
+                    .await?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::prelude::*,
+    prelude::*,
+};
+
+use crate::{
+    checks::*,
+    data::PoolContainer,
+    db::{get_crossfade, set_crossfade},
+    fades::MAX_CROSSFADE,
+    invoker::Invoker,
+    queue::QueueMap,
+};
+
+#[command]
+#[aliases("fade")]
+#[checks(admin_only)]
+#[description = "Shows or changes how many seconds songs fade into each other, 0 turns it off"]
+#[usage = "<number of seconds>"]
+#[bucket = "global"]
+async fn crossfade(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let seconds = match args.single_quoted::<String>() {
+        Ok(seconds) => match seconds.parse::<u64>() {
+            Ok(seconds) => Some(seconds),
+            Err(_) => {
+                msg.reply_ping(ctx, "Must be a number of seconds").await?;
+                return Ok(());
+            }
+        },
+        Err(_) => None,
+    };
+
+    change_crossfade(ctx, msg.into(), seconds).await
+}
+
+pub async fn change_crossfade(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    seconds: Option<u64>,
+) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let data = ctx.data.read().await;
+    let pool = data.get::<PoolContainer>().unwrap();
+
+    let crossfade = match seconds {
+        Some(seconds) => Duration::from_secs(seconds),
+        None => {
+            let crossfade = get_crossfade(pool, guild_id.into()).await?;
+
+            if crossfade.is_zero() {
+                invoker.say(ctx, "Crossfading is off").await?;
+            } else {
+                invoker
+                    .say(
+                        ctx,
+                        format!(
+                            "Songs fade into each other over {} seconds",
+                            crossfade.as_secs()
+                        ),
+                    )
+                    .await?;
+            }
+
+            return Ok(());
+        }
+    };
+
+    if crossfade > MAX_CROSSFADE {
+        invoker
+            .reply(
+                ctx,
+                format!(
+                    "Crossfading can be at most {} seconds long",
+                    MAX_CROSSFADE.as_secs()
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    set_crossfade(pool, guild_id.into(), crossfade).await?;
+
+    let queue_container = data.get::<QueueMap>().unwrap().clone();
+    let queue = queue_container.get(&guild_id).map(|queue| queue.clone());
+
+    if let Some(queue) = queue {
+        queue.set_crossfade(crossfade);
+    }
+
+    if crossfade.is_zero() {
+        invoker.say(ctx, "Turned off crossfading").await?;
+    } else {
+        invoker
+            .say(
+                ctx,
+                format!(
+                    "Songs now fade into each other over {} seconds",
+                    crossfade.as_secs()
+                ),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
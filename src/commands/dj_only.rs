@@ -8,6 +8,7 @@ use crate::{
     checks::*,
     data::DjOnlyContainer,
     dj_only_store::{check_if_guild_in_store, delete_guild_from_store, insert_guild_into_store},
+    invoker::Invoker,
 };
 
 #[command]
@@ -15,17 +16,21 @@ use crate::{
 #[description = "Enables/Disables dj only mode"]
 #[bucket = "global"]
 async fn dj_only(ctx: &Context, msg: &Message) -> CommandResult {
-    let guild_id = msg.guild_id.unwrap();
+    toggle_dj_only(ctx, msg.into()).await
+}
+
+pub async fn toggle_dj_only(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild_id = invoker.guild_id();
 
     let data = ctx.data.read().await;
     let redis_con = data.get::<DjOnlyContainer>().unwrap().clone();
 
     if check_if_guild_in_store(redis_con.clone(), guild_id).await? {
         delete_guild_from_store(redis_con.clone(), guild_id).await?;
-        msg.channel_id.say(ctx, "Disabled dj only mode").await?;
+        invoker.say(ctx, "Disabled dj only mode").await?;
     } else {
         insert_guild_into_store(redis_con, guild_id).await?;
-        msg.channel_id.say(ctx, "Enabled dj only mode").await?;
+        invoker.say(ctx, "Enabled dj only mode").await?;
     }
 
     Ok(())
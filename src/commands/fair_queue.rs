@@ -0,0 +1,52 @@
+use serenity::{
+    framework::standard::{macros::command, CommandResult},
+    model::prelude::*,
+    prelude::*,
+};
+
+use crate::{
+    checks::*,
+    data::PoolContainer,
+    db::{get_fair_queue, set_fair_queue},
+    invoker::Invoker,
+    queue::QueueMap,
+};
+
+#[command("fairqueue")]
+#[checks(admin_only)]
+#[description = "Enables/Disables fair queue mode, where upcoming songs take turns between the people who requested them"]
+#[bucket = "global"]
+async fn fair_queue(ctx: &Context, msg: &Message) -> CommandResult {
+    toggle_fair_queue(ctx, msg.into()).await
+}
+
+pub async fn toggle_fair_queue(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let data = ctx.data.read().await;
+    let pool = data.get::<PoolContainer>().unwrap();
+
+    let fair_queue = !get_fair_queue(pool, guild_id.into()).await?;
+
+    set_fair_queue(pool, guild_id.into(), fair_queue).await?;
+
+    let queue_container = data.get::<QueueMap>().unwrap().clone();
+    let queue = queue_container.get(&guild_id).map(|queue| queue.clone());
+
+    if let Some(queue) = queue {
+        queue.set_fair_queue(fair_queue);
+    }
+
+    if fair_queue {
+        invoker
+            .say(
+                ctx,
+                "Enabled fair queue mode, upcoming songs now take turns between requesters",
+            )
+            .await?;
+    } else {
+        invoker.say(ctx, "Disabled fair queue mode").await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,332 @@
+use std::{
+    convert::{TryFrom, TryInto},
+    time::Duration,
+};
+
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::prelude::*,
+    prelude::*,
+    utils::Color,
+};
+
+use super::{
+    play::{add_tracks_within_limits, get_or_join_handler},
+    util::format_duration_to_mm_ss,
+};
+use crate::{
+    checks::*,
+    data::PoolContainer,
+    db::{add_favourite, get_favourites, remove_favourite, Favourite},
+    invoker::Invoker,
+    playlists::get_start_time_from_url,
+    queue::{get_existing_queue, get_queue_from_ctx_and_guild_id, QueuedTrack},
+};
+
+fn from_favourite(favourite: Favourite, requester: UserId) -> QueuedTrack {
+    QueuedTrack {
+        start_time: get_start_time_from_url(&favourite.name),
+        title: favourite.title,
+        url: favourite.url,
+        duration: favourite
+            .duration_ms
+            .and_then(|duration_ms| duration_ms.try_into().ok())
+            .map(Duration::from_millis),
+        ..QueuedTrack::new(favourite.name, Some(requester))
+    }
+}
+
+#[command]
+#[checks(not_blacklisted)]
+#[description = "Sends you the currently playing song in a DM and adds it to your favourites"]
+#[bucket = "global"]
+async fn grab(ctx: &Context, msg: &Message) -> CommandResult {
+    grab_track(ctx, msg.into()).await
+}
+
+#[command]
+#[aliases("favs")]
+#[checks(not_blacklisted)]
+#[description = "Lists your favourite songs, use ~grab to add the current song and ~play favourites to queue them"]
+#[sub_commands(favourites_remove)]
+#[bucket = "global"]
+async fn favourites(ctx: &Context, msg: &Message) -> CommandResult {
+    show_favourites(ctx, msg.into()).await
+}
+
+#[command("remove")]
+#[checks(not_blacklisted)]
+#[description = "Removes a song from your favourites, use ~favourites to see what index to use"]
+#[usage = "<index of song>"]
+#[bucket = "global"]
+async fn favourites_remove(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let index = args.single::<usize>()?;
+
+    remove_from_favourites(ctx, msg.into(), index).await
+}
+
+pub async fn grab_track(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let queue = match get_existing_queue(ctx, guild_id).await {
+        Some(queue) => queue,
+        None => {
+            invoker.reply(ctx, "Nothing playing").await?;
+            return Ok(());
+        }
+    };
+
+    let current = { queue.current().lock().clone() };
+
+    let handle = match current {
+        Some(handle) => handle,
+        None => {
+            invoker.reply(ctx, "Nothing playing").await?;
+            return Ok(());
+        }
+    };
+
+    let metadata = handle.metadata();
+    let position = handle
+        .get_info()
+        .await
+        .map(|state| state.position)
+        .unwrap_or_default();
+
+    let queued_track = queue
+        .current_queue()
+        .into_iter()
+        .find(|track| track.uuid == handle.uuid());
+
+    let title = metadata
+        .title
+        .clone()
+        .or_else(|| {
+            queued_track
+                .as_ref()
+                .map(|track| track.display_title().to_string())
+        })
+        .unwrap_or_default();
+    let url = metadata
+        .source_url
+        .clone()
+        .or_else(|| queued_track.as_ref().and_then(|track| track.url.clone()));
+
+    let guild = invoker.guild(ctx).await;
+
+    let dm = invoker
+        .author_id()
+        .create_dm_channel(ctx)
+        .await?
+        .send_message(ctx, |m| {
+            m.embed(|e| {
+                match &url {
+                    Some(url) => e.description(format!("[{title}]({url})")),
+                    None => e.description(&title),
+                };
+
+                if let Some(uploader) = &metadata.channel {
+                    e.field("Uploader", uploader, true);
+                }
+
+                e.field(
+                    "Grabbed at",
+                    format!(
+                        "`{} / {}`",
+                        format_duration_to_mm_ss(position),
+                        metadata
+                            .duration
+                            .map_or_else(|| "?:??".to_string(), format_duration_to_mm_ss)
+                    ),
+                    true,
+                );
+
+                if let Some(thumbnail) = &metadata.thumbnail {
+                    e.thumbnail(thumbnail);
+                }
+
+                e.title("Grabbed song")
+                    .footer(|f| f.text(format!("Playing in {}", guild.name)))
+                    .color(Color::DARK_GREEN)
+            })
+        })
+        .await;
+
+    if dm.is_err() {
+        invoker
+            .reply(
+                ctx,
+                "Could not DM you, check if you allow DMs from server members",
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let name = match queued_track.map(|track| track.name).or_else(|| url.clone()) {
+        Some(name) => name,
+        None => {
+            invoker.reply(ctx, "Sent you the song in a DM").await?;
+            return Ok(());
+        }
+    };
+
+    let favourite = Favourite {
+        name,
+        title: Some(title),
+        url,
+        duration_ms: metadata
+            .duration
+            .map(|duration| i64::try_from(duration.as_millis()).unwrap_or_default()),
+    };
+
+    let added = {
+        let data = ctx.data.read().await;
+        let pool = data.get::<PoolContainer>().unwrap();
+
+        add_favourite(pool, invoker.author_id().into(), &favourite).await?
+    };
+
+    if added {
+        invoker
+            .reply(
+                ctx,
+                "Sent you the song in a DM and added it to your favourites",
+            )
+            .await?;
+    } else {
+        invoker
+            .reply(
+                ctx,
+                "Sent you the song in a DM, it is already in your favourites",
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn show_favourites(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let favourites = {
+        let data = ctx.data.read().await;
+        let pool = data.get::<PoolContainer>().unwrap();
+
+        get_favourites(pool, invoker.author_id().into()).await?
+    };
+
+    if favourites.is_empty() {
+        invoker
+            .say(
+                ctx,
+                "You have no favourites yet, use ~grab while a song is playing to add it",
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let mut description = favourites
+        .iter()
+        .take(25)
+        .enumerate()
+        .map(|(idx, favourite)| {
+            format!(
+                "`{}` {}",
+                idx + 1,
+                favourite.title.as_deref().unwrap_or(&favourite.name)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if favourites.len() > 25 {
+        description.push_str(&format!("\nand {} more", favourites.len() - 25));
+    }
+
+    invoker
+        .send_embed(ctx, |e| {
+            e.title("Favourites")
+                .description(description)
+                .footer(|f| f.text(format!("{} songs", favourites.len())))
+                .color(Color::DARK_GREEN)
+        })
+        .await?;
+
+    Ok(())
+}
+
+pub async fn remove_from_favourites(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    index: usize,
+) -> CommandResult {
+    let data = ctx.data.read().await;
+    let pool = data.get::<PoolContainer>().unwrap();
+
+    let removed = match index.checked_sub(1) {
+        Some(index) => {
+            remove_favourite(pool, invoker.author_id().into(), index.try_into()?).await?
+        }
+        None => None,
+    };
+
+    match removed {
+        Some(favourite) => {
+            let title = favourite.title.unwrap_or(favourite.name);
+
+            invoker
+                .say(ctx, format!("Removed `{title}` from your favourites"))
+                .await?;
+        }
+        None => {
+            invoker
+                .reply(ctx, format!("There is no song at index: {index}"))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Adds every favourite of the author to the queue, used by `play favourites`.
+pub async fn play_favourites(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let favourites = {
+        let data = ctx.data.read().await;
+        let pool = data.get::<PoolContainer>().unwrap();
+
+        get_favourites(pool, invoker.author_id().into()).await?
+    };
+
+    if favourites.is_empty() {
+        invoker
+            .reply(
+                ctx,
+                "You have no favourites yet, use ~grab while a song is playing to add it",
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let handler_lock = match get_or_join_handler(ctx, invoker).await? {
+        Some(handler_lock) => handler_lock,
+        None => return Ok(()),
+    };
+
+    let mut reply_msg = invoker
+        .send_embed(ctx, |e| e.title("Loading your favourites..."))
+        .await?;
+
+    let queue = get_queue_from_ctx_and_guild_id(ctx, invoker.guild_id()).await;
+    let tracks = favourites
+        .into_iter()
+        .map(|favourite| from_favourite(favourite, invoker.author_id()))
+        .collect();
+
+    let count = add_tracks_within_limits(ctx, invoker, &queue, handler_lock, tracks).await?;
+
+    invoker
+        .edit_embed(ctx, &mut reply_msg, |e| {
+            e.title(format!("Added {count} songs from your favourites"))
+        })
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::prelude::*,
+    prelude::*,
+};
+
+use crate::{
+    checks::*,
+    filters::{format_filters, toggle_filter, AudioFilter},
+    invoker::Invoker,
+    queue::get_queue_from_ctx_and_guild_id,
+};
+
+#[command]
+#[aliases("filters", "fx")]
+#[checks(dj_only)]
+#[description = "Shows the active audio filters, or turns a filter on or off for the current and upcoming tracks"]
+#[usage = "<bassboost | nightcore | vaporwave | karaoke | tremolo | 8d | tempo <0.5-2> | pitch <0.5-2> | off>"]
+#[bucket = "global"]
+async fn filter(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let name = args.single_quoted::<String>().ok();
+    let value = args.single_quoted::<String>().ok();
+
+    change_filter(ctx, msg.into(), name, value).await
+}
+
+/// Without a name the active filters are shown, `off` turns all of them off.
+pub async fn change_filter(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    name: Option<String>,
+    value: Option<String>,
+) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let manager = songbird::get(ctx).await.unwrap().clone();
+
+    let handler_lock = match manager.get(guild_id) {
+        Some(handler_lock) => handler_lock,
+        None => {
+            invoker
+                .reply(ctx, "Not in a voice channel to apply filters in")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
+    let mut filters = queue.filters();
+
+    let response = match name.map(|name| name.to_lowercase()).as_deref() {
+        None => {
+            if filters.is_empty() {
+                invoker
+                    .say(ctx, "No filters are on, use ~filter <name> to turn one on")
+                    .await?;
+            } else {
+                invoker
+                    .say(ctx, format!("Active filters: {}", format_filters(&filters)))
+                    .await?;
+            }
+
+            return Ok(());
+        }
+        Some("off" | "clear" | "none") => {
+            if filters.is_empty() {
+                invoker.reply(ctx, "No filters are on").await?;
+                return Ok(());
+            }
+
+            filters.clear();
+            "Turned off all filters".to_string()
+        }
+        Some(name) => {
+            let filter = match AudioFilter::parse(name, value.as_deref()) {
+                Ok(filter) => filter,
+                Err(e) => {
+                    invoker.reply(ctx, e).await?;
+                    return Ok(());
+                }
+            };
+
+            let state = if toggle_filter(&mut filters, filter) {
+                "on"
+            } else {
+                "off"
+            };
+
+            if filters.is_empty() {
+                format!("Turned {state} {filter}")
+            } else {
+                format!(
+                    "Turned {state} {filter}, active filters: {}",
+                    format_filters(&filters)
+                )
+            }
+        }
+    };
+
+    queue.set_filters(filters);
+
+    // Restarting the track can take a moment, so the response goes out first
+    invoker.say(ctx, response).await?;
+
+    queue
+        .reload_current(handler_lock, invoker.channel_id(), ctx.http.clone())
+        .await?;
+
+    Ok(())
+}
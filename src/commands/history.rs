@@ -0,0 +1,195 @@
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::prelude::*,
+    prelude::*,
+    utils::Color,
+};
+use uuid::Uuid;
+
+use super::util::track_details;
+use crate::{
+    checks::*,
+    data::PoolContainer,
+    history::{load_history, load_saved_history},
+    invoker::Invoker,
+    queue::{get_queue_from_ctx_and_guild_id, QueueMap, QueuedTrack},
+};
+
+#[command]
+#[checks(not_blacklisted)]
+#[description = "Shows the recently played tracks"]
+#[bucket = "global"]
+async fn history(ctx: &Context, msg: &Message) -> CommandResult {
+    show_history(ctx, msg.into()).await
+}
+
+#[command]
+#[aliases("back")]
+#[checks(dj_only)]
+#[description = "Plays the previous track again"]
+#[bucket = "global"]
+async fn previous(ctx: &Context, msg: &Message) -> CommandResult {
+    play_previous(ctx, msg.into()).await
+}
+
+#[command]
+#[checks(dj_only)]
+#[description = "Adds a track from the history back to the queue, use ~history to see what index to use"]
+#[usage = "<index of track in the history>"]
+#[bucket = "global"]
+async fn replay(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let index = args.single::<usize>()?;
+
+    replay_track(ctx, msg.into(), index).await
+}
+
+/// There is no queue after the bot left the voice channel, the saved history is used then.
+async fn get_guild_history(ctx: &Context, guild_id: GuildId) -> anyhow::Result<Vec<QueuedTrack>> {
+    let data = ctx.data.read().await;
+    let pool = data.get::<PoolContainer>().unwrap();
+    let queue_container = data.get::<QueueMap>().unwrap().clone();
+    let queue = queue_container.get(&guild_id).map(|queue| queue.clone());
+
+    match queue {
+        Some(queue) => load_history(pool, guild_id, &queue).await,
+        None => load_saved_history(pool, guild_id).await,
+    }
+}
+
+async fn get_history_track(
+    ctx: &Context,
+    guild_id: GuildId,
+    index: usize,
+) -> anyhow::Result<Option<QueuedTrack>> {
+    let history = get_guild_history(ctx, guild_id).await?;
+
+    Ok(history.into_iter().nth(index))
+}
+
+pub async fn show_history(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let history = get_guild_history(ctx, guild_id).await?;
+
+    if history.is_empty() {
+        invoker.say(ctx, "Nothing was played yet").await?;
+        return Ok(());
+    }
+
+    let guild = invoker.guild(ctx).await;
+
+    let description = history
+        .iter()
+        .take(15)
+        .enumerate()
+        .map(|(idx, track)| {
+            format!(
+                "`{}` {} | {}",
+                idx + 1,
+                track.display_title(),
+                track_details(track, &guild)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    invoker
+        .send_embed(ctx, |e| {
+            e.title("Recently played")
+                .description(description)
+                .color(Color::DARK_GREEN)
+        })
+        .await?;
+
+    Ok(())
+}
+
+pub async fn play_previous(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let manager = songbird::get(ctx).await.unwrap().clone();
+
+    let handler_lock = match manager.get(guild_id) {
+        Some(handler_lock) => handler_lock,
+        None => {
+            invoker
+                .reply(ctx, "Not in a voice channel to play in")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let track = match get_history_track(ctx, guild_id, 0).await? {
+        Some(track) => QueuedTrack {
+            uuid: Uuid::new_v4(),
+            requester: Some(invoker.author_id()),
+            ..track
+        },
+        None => {
+            invoker.reply(ctx, "Nothing was played yet").await?;
+            return Ok(());
+        }
+    };
+
+    let title = track.display_title().to_string();
+
+    let mut queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
+
+    if queue.is_empty() {
+        queue
+            .add(track, handler_lock, invoker.channel_id(), ctx.http.clone())
+            .await?;
+    } else {
+        // Put the previous track after the current one, then skip to it
+        queue.modify_queue(|queue| queue.insert(1, track));
+        queue.skip()?;
+    }
+
+    invoker.say(ctx, format!("Playing `{title}` again")).await?;
+
+    Ok(())
+}
+
+pub async fn replay_track(ctx: &Context, invoker: Invoker<'_>, index: usize) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let manager = songbird::get(ctx).await.unwrap().clone();
+
+    let handler_lock = match manager.get(guild_id) {
+        Some(handler_lock) => handler_lock,
+        None => {
+            invoker
+                .reply(ctx, "Not in a voice channel to play in")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let track = match get_history_track(ctx, guild_id, index.saturating_sub(1)).await? {
+        Some(track) if index > 0 => QueuedTrack {
+            uuid: Uuid::new_v4(),
+            requester: Some(invoker.author_id()),
+            ..track
+        },
+        _ => {
+            invoker
+                .reply(ctx, format!("There is no track at index: {index}"))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let title = track.display_title().to_string();
+
+    let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
+
+    queue
+        .add(track, handler_lock, invoker.channel_id(), ctx.http.clone())
+        .await?;
+
+    invoker
+        .say(ctx, format!("Added `{title}` back to the queue"))
+        .await?;
+
+    Ok(())
+}
@@ -7,25 +7,32 @@ use serenity::{
 };
 use songbird::Event;
 
-use crate::{checks::*, queue::QueueMap, voice_events::ChannelIdleChecker};
+use crate::{
+    checks::*, data::PoolContainer, invoker::Invoker, queue::QueueMap,
+    queue_settings::load_queue_settings, voice_events::ChannelIdleChecker,
+};
 
 #[command]
 #[checks(dj_only)]
 #[description = "Makes the bot join the voice channel you are in"]
 #[bucket = "global"]
 async fn join(ctx: &Context, msg: &Message) -> CommandResult {
-    let guild = msg.guild(&ctx.cache).await.unwrap();
+    join_channel(ctx, msg.into()).await
+}
+
+pub async fn join_channel(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild = invoker.guild(ctx).await;
     let guild_id = guild.id;
 
     let channel_id = guild
         .voice_states
-        .get(&msg.author.id)
+        .get(&invoker.author_id())
         .and_then(|voice_state| voice_state.channel_id);
 
     let connect_to = match channel_id {
         Some(channel) => channel,
         None => {
-            msg.reply_ping(ctx, "Not in a voice channel").await?;
+            invoker.reply(ctx, "Not in a voice channel").await?;
 
             return Ok(());
         }
@@ -34,9 +41,7 @@ async fn join(ctx: &Context, msg: &Message) -> CommandResult {
     let manager = songbird::get(ctx).await.unwrap().clone();
 
     if manager.get(guild_id).is_some() {
-        msg.channel_id
-            .say(ctx, "Already in a voice channel")
-            .await?;
+        invoker.say(ctx, "Already in a voice channel").await?;
         return Ok(());
     }
 
@@ -46,14 +51,14 @@ async fn join(ctx: &Context, msg: &Message) -> CommandResult {
         let mut handler = handler_lock.lock().await;
         let data = ctx.data.read().await;
         let queue_container = data.get::<QueueMap>().unwrap().clone();
-        let queue = queue_container.entry(guild_id).or_default();
+        let queue = queue_container.entry(guild_id).or_default().clone();
 
         handler.add_global_event(
             Event::Periodic(Duration::from_secs(60), None),
             ChannelIdleChecker {
                 handler_lock: handler_lock.clone(),
                 elapsed: Default::default(),
-                chan_id: msg.channel_id,
+                chan_id: invoker.channel_id(),
                 guild_id,
                 http: ctx.http.clone(),
                 cache: ctx.cache.clone(),
@@ -61,11 +66,14 @@ async fn join(ctx: &Context, msg: &Message) -> CommandResult {
             },
         );
 
-        msg.channel_id
+        let pool = data.get::<PoolContainer>().unwrap();
+        load_queue_settings(pool, guild_id, &queue).await?;
+
+        invoker
             .say(ctx, format!("Joined {}", connect_to.mention()))
             .await?;
     } else {
-        msg.channel_id.say(ctx, "Error joining the channel").await?;
+        invoker.say(ctx, "Error joining the channel").await?;
     }
 
     Ok(())
@@ -0,0 +1,250 @@
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::prelude::*,
+    prelude::*,
+    utils::Color,
+};
+
+use super::{
+    play::{check_queue_limits, get_or_join_handler, get_queue_limits_for},
+    util::format_duration_to_mm_ss,
+};
+use crate::{
+    checks::*,
+    invoker::Invoker,
+    library::{library_configured, scan_library, search_library, LibraryCache, LibraryTrack},
+    queue::get_queue_from_ctx_and_guild_id,
+};
+
+const MAX_RESULTS: usize = 10;
+
+const NOT_CONFIGURED: &str = "There is no music library set up for this bot";
+
+#[command]
+#[checks(not_blacklisted)]
+#[description = "Shows the local music library, use ~library search and ~library play to find and queue songs from it"]
+#[sub_commands(library_search, library_play, library_rescan)]
+#[bucket = "global"]
+async fn library(ctx: &Context, msg: &Message) -> CommandResult {
+    show_library(ctx, msg.into()).await
+}
+
+#[command("search")]
+#[checks(not_blacklisted)]
+#[description = "Searches the local music library by title, artist or file name"]
+#[usage = "<title, artist or file name>"]
+#[bucket = "global"]
+async fn library_search(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let query = args.remains().map(|query| query.to_string());
+
+    search_library_tracks(ctx, msg.into(), query).await
+}
+
+#[command("play")]
+#[checks(dj_only)]
+#[description = "Adds the best match from the local music library to the queue"]
+#[usage = "<title, artist or file name>"]
+#[bucket = "global"]
+async fn library_play(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let query = args.remains().map(|query| query.to_string());
+
+    play_library_track(ctx, msg.into(), query).await
+}
+
+#[command("rescan")]
+#[owners_only]
+#[help_available(false)]
+async fn library_rescan(ctx: &Context, msg: &Message) -> CommandResult {
+    rescan_library(ctx, msg.into()).await
+}
+
+/// The scanned library, it is scanned the first time it is used.
+async fn get_library(ctx: &Context) -> anyhow::Result<Vec<LibraryTrack>> {
+    let cache = {
+        let data = ctx.data.read().await;
+        data.get::<LibraryCache>().unwrap().clone()
+    };
+
+    if let Some(tracks) = cache.read().await.as_ref() {
+        return Ok(tracks.clone());
+    }
+
+    // Scanning takes a while, the cache is not locked until it is done
+    let tracks = scan_library().await?;
+
+    let mut cached = cache.write().await;
+
+    Ok(cached.get_or_insert(tracks).clone())
+}
+
+fn format_library_track(track: &LibraryTrack) -> String {
+    format!(
+        "{} `{}`",
+        track.display_title(),
+        track
+            .duration
+            .map_or_else(|| "?:??".to_string(), format_duration_to_mm_ss)
+    )
+}
+
+pub async fn show_library(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    if !library_configured() {
+        invoker.say(ctx, NOT_CONFIGURED).await?;
+        return Ok(());
+    }
+
+    let tracks = get_library(ctx).await?;
+
+    invoker
+        .send_embed(ctx, |e| {
+            e.title("Music library")
+                .description(format!(
+                    "{} songs, use ~library search to find songs and ~library play to queue them",
+                    tracks.len()
+                ))
+                .color(Color::DARK_GREEN)
+        })
+        .await?;
+
+    Ok(())
+}
+
+pub async fn search_library_tracks(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    query: Option<String>,
+) -> CommandResult {
+    if !library_configured() {
+        invoker.say(ctx, NOT_CONFIGURED).await?;
+        return Ok(());
+    }
+
+    let query = match query {
+        Some(query) => query,
+        None => {
+            invoker
+                .reply(
+                    ctx,
+                    "Must provide a title, artist or file name to search for",
+                )
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let tracks = get_library(ctx).await?;
+    let results = search_library(&tracks, &query);
+
+    if results.is_empty() {
+        invoker
+            .reply(ctx, format!("No songs in the library match `{query}`"))
+            .await?;
+        return Ok(());
+    }
+
+    let mut description = results
+        .iter()
+        .take(MAX_RESULTS)
+        .enumerate()
+        .map(|(idx, track)| format!("`{}` {}", idx + 1, format_library_track(track)))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if results.len() > MAX_RESULTS {
+        description.push_str(&format!("\nand {} more", results.len() - MAX_RESULTS));
+    }
+
+    invoker
+        .send_embed(ctx, |e| {
+            e.title(format!("Library results for `{query}`"))
+                .description(description)
+                .color(Color::DARK_GREEN)
+        })
+        .await?;
+
+    Ok(())
+}
+
+pub async fn play_library_track(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    query: Option<String>,
+) -> CommandResult {
+    if !library_configured() {
+        invoker.say(ctx, NOT_CONFIGURED).await?;
+        return Ok(());
+    }
+
+    let query = match query {
+        Some(query) => query,
+        None => {
+            invoker
+                .reply(ctx, "Must provide a title, artist or file name to play")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let tracks = get_library(ctx).await?;
+
+    let track = match search_library(&tracks, &query).first() {
+        Some(track) => track.to_queued_track(invoker.author_id()),
+        None => {
+            invoker
+                .reply(ctx, format!("No songs in the library match `{query}`"))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let handler_lock = match get_or_join_handler(ctx, invoker).await? {
+        Some(handler_lock) => handler_lock,
+        None => return Ok(()),
+    };
+
+    let limits = get_queue_limits_for(ctx, invoker).await?;
+    let queue = get_queue_from_ctx_and_guild_id(ctx, invoker.guild_id()).await;
+
+    if let Err(e) = check_queue_limits(&limits, &queue, invoker.author_id(), track.duration) {
+        invoker.reply(ctx, e).await?;
+        return Ok(());
+    }
+
+    let title = track.display_title().to_string();
+
+    queue
+        .add(track, handler_lock, invoker.channel_id(), ctx.http.clone())
+        .await?;
+
+    invoker
+        .say(
+            ctx,
+            format!("Added `{title}` from the library to the queue"),
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn rescan_library(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    if !library_configured() {
+        invoker.say(ctx, NOT_CONFIGURED).await?;
+        return Ok(());
+    }
+
+    let cache = {
+        let data = ctx.data.read().await;
+        data.get::<LibraryCache>().unwrap().clone()
+    };
+
+    let tracks = scan_library().await?;
+    let count = tracks.len();
+
+    *cache.write().await = Some(tracks);
+
+    invoker
+        .say(ctx, format!("Rescanned the library, found {count} songs"))
+        .await?;
+
+    Ok(())
+}
@@ -1,40 +1,71 @@
 use serenity::{
-    framework::standard::{macros::command, CommandResult},
+    framework::standard::{macros::command, Args, CommandResult},
     model::prelude::*,
     prelude::*,
 };
-use songbird::tracks::LoopState;
 
-use crate::{checks::*, queue::get_queue_from_ctx_and_guild_id};
+use crate::{
+    checks::*,
+    invoker::Invoker,
+    queue::{get_queue_from_ctx_and_guild_id, RepeatMode},
+};
 
 #[command("loop")]
+#[aliases("repeat")]
 #[checks(dj_only)]
-#[description = "Enables/Disables a loop for the current track"]
+#[description = "Sets what gets repeated, without an option it enables/disables a loop for the current track"]
+#[usage = "<off | track | queue | number of times to repeat the track>"]
 #[bucket = "global"]
-async fn loop_command(ctx: &Context, msg: &Message) -> CommandResult {
-    let guild_id = msg.guild_id.unwrap();
+async fn loop_command(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let repeat_mode = match args.single_quoted::<String>() {
+        Ok(option) => match option.parse::<RepeatMode>() {
+            Ok(repeat_mode) => Some(repeat_mode),
+            Err(e) => {
+                msg.reply_ping(ctx, e).await?;
+                return Ok(());
+            }
+        },
+        Err(_) => None,
+    };
+
+    set_repeat_mode(ctx, msg.into(), repeat_mode).await
+}
+
+/// Without a mode the loop for the current track is toggled.
+pub async fn set_repeat_mode(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    repeat_mode: Option<RepeatMode>,
+) -> CommandResult {
+    let guild_id = invoker.guild_id();
 
     let manager = songbird::get(ctx).await.unwrap().clone();
 
     if manager.get(guild_id).is_some() {
         let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
-        let current = { queue.current().lock().clone() };
 
-        if let Some(handle) = current {
-            if let LoopState::Infinite = handle.get_info().await?.loops {
-                handle.disable_loop()?;
+        let repeat_mode = repeat_mode.unwrap_or_else(|| match queue.repeat_mode() {
+            RepeatMode::Off => RepeatMode::Track,
+            _ => RepeatMode::Off,
+        });
 
-                msg.channel_id.say(ctx, "Disabled loop").await?;
-            } else {
-                handle.enable_loop()?;
+        queue.set_repeat_mode(repeat_mode)?;
 
-                msg.channel_id.say(ctx, "Enabled loop").await?;
-            }
-        }
+        invoker
+            .say(ctx, format!("Repeat mode set to {repeat_mode}"))
+            .await?;
     } else {
-        msg.reply_ping(ctx, "Not in a voice channel to loop in")
+        invoker
+            .reply(ctx, "Not in a voice channel to loop in")
             .await?;
     }
 
     Ok(())
 }
+
+/// Switches to the next repeat mode, off -> track -> queue -> off.
+pub async fn cycle_repeat_mode(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let queue = get_queue_from_ctx_and_guild_id(ctx, invoker.guild_id()).await;
+
+    set_repeat_mode(ctx, invoker, Some(queue.repeat_mode().next())).await
+}
@@ -5,17 +5,27 @@ use serenity::{
     utils::Color,
 };
 
-use crate::{checks::*, lyrics_api::get_lyrics, queue::get_queue_from_ctx_and_guild_id};
+use crate::{
+    checks::*, invoker::Invoker, lyrics_api::get_lyrics, queue::get_queue_from_ctx_and_guild_id,
+};
 
 #[command]
 #[checks(not_blacklisted)]
 #[description = "Shows the lyrics to a song.  If no arguments are provided it will show the lyrics of the currently playing song"]
 #[bucket = "global"]
 async fn lyrics(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
-    let guild_id = msg.guild_id.unwrap();
+    show_lyrics(ctx, msg.into(), args.remains().map(|name| name.to_string())).await
+}
 
-    let name_of_song = match args.remains() {
-        Some(name) => name.to_string(),
+pub async fn show_lyrics(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    name_of_song: Option<String>,
+) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let name_of_song = match name_of_song {
+        Some(name) => name,
         None => {
             let manager = songbird::get(ctx).await.unwrap().clone();
 
@@ -29,24 +39,24 @@ async fn lyrics(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
                     let artist = metadata.artist.clone().unwrap();
                     format!("{title} {artist}")
                 } else {
-                    msg.channel_id.say(ctx, "Nothing playing").await?;
+                    invoker.say(ctx, "Nothing playing").await?;
                     return Ok(());
                 }
             } else {
-                msg.channel_id.say(ctx, "Nothing playing").await?;
+                invoker.say(ctx, "Nothing playing").await?;
                 return Ok(());
             }
         }
     };
 
-    msg.channel_id
+    invoker
         .say(ctx, format!("Searching the lyrics for {name_of_song}"))
         .await?;
 
     let song_data = match get_lyrics(ctx, name_of_song.clone()).await? {
         Some(data) => data,
         None => {
-            msg.channel_id
+            invoker
                 .say(ctx, format!("Could not find lyrics for {name_of_song}"))
                 .await?;
             return Ok(());
@@ -65,40 +75,36 @@ async fn lyrics(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
             .collect::<Vec<&str>>();
 
         for (idx, sub) in subs.iter().enumerate() {
-            msg.channel_id
-                .send_message(ctx, |m| {
-                    m.embed(|e| {
-                        if idx == 0 {
-                            e.title(format!("Lyrics for `{name}` by `{artist}`"));
-                        }
+            invoker
+                .send_embed(ctx, |e| {
+                    if idx == 0 {
+                        e.title(format!("Lyrics for `{name}` by `{artist}`"));
+                    }
 
-                        e.description(sub);
+                    e.description(sub);
 
-                        if idx == subs.len() - 1 {
-                            e.footer(|f| f.text("Lyrics provided by KSoft.Si"));
-                        }
+                    if idx == subs.len() - 1 {
+                        e.footer(|f| f.text("Lyrics provided by KSoft.Si"));
+                    }
 
-                        e.color(Color::DARK_GREEN);
+                    e.color(Color::DARK_GREEN);
 
-                        e
-                    })
+                    e
                 })
                 .await?;
         }
     } else {
-        msg.channel_id
-            .send_message(ctx, |m| {
-                m.embed(|e| {
-                    e.title(format!("Lyrics for `{name}` by `{artist}`"));
+        invoker
+            .send_embed(ctx, |e| {
+                e.title(format!("Lyrics for `{name}` by `{artist}`"));
 
-                    e.description(lyrics);
+                e.description(lyrics);
 
-                    e.footer(|f| f.text("Lyrics provided by KSoft.Si"));
+                e.footer(|f| f.text("Lyrics provided by KSoft.Si"));
 
-                    e.color(Color::DARK_GREEN);
+                e.color(Color::DARK_GREEN);
 
-                    e
-                })
+                e
             })
             .await?;
     }
@@ -1,33 +1,53 @@
+pub mod autoplay;
+pub mod crossfade;
 pub mod db_testing;
 pub mod dj_only;
+pub mod fair_queue;
+pub mod favourites;
+pub mod filter;
 pub mod help;
+pub mod history;
 pub mod info;
 pub mod join;
+pub mod library;
 pub mod loop_command;
 pub mod lyrics;
+pub mod move_command;
 pub mod mute;
+pub mod normalise;
 pub mod now_playing;
 pub mod pause;
 pub mod perms;
 pub mod ping;
 pub mod play;
+pub mod play_next;
+pub mod playlist;
 pub mod prefix;
 pub mod queue;
+pub mod queue_limits;
 pub mod remove;
 pub mod restart;
 pub mod resume;
+pub mod search;
+pub mod seek;
 pub mod shuffle;
 pub mod skip;
+pub mod skip_to;
+pub mod slash;
+pub mod station;
 pub mod stop;
 pub mod volume;
+pub mod voteskip;
 
-mod util {
-    use std::time::Duration;
+pub(crate) mod util {
+    use std::{ops::RangeInclusive, time::Duration};
 
-    use serenity::utils::MessageBuilder;
+    use serenity::{model::guild::Guild, utils::MessageBuilder};
 
     use songbird::tracks::{PlayMode, TrackHandle};
 
+    use crate::queue::QueuedTrack;
+
     pub fn format_duration_to_mm_ss(duration: Duration) -> String {
         let seconds = duration.as_secs() % 60;
         let minutes = (duration.as_secs() / 60) % 60;
@@ -40,6 +60,110 @@ mod util {
         }
     }
 
+    /// `LIVE` for live streams, otherwise the length of the track if it is known.
+    pub fn format_track_length(track: &QueuedTrack) -> String {
+        if track.live {
+            "LIVE".to_string()
+        } else {
+            track
+                .duration
+                .map_or_else(|| "?:??".to_string(), format_duration_to_mm_ss)
+        }
+    }
+
+    /// Length, uploader and requester of a queued track, separated by `|`.
+    pub fn track_details(track: &QueuedTrack, guild: &Guild) -> String {
+        let mut details = vec![format_track_length(track)];
+
+        if let Some(uploader) = &track.uploader {
+            details.push(uploader.clone());
+        }
+
+        if let Some(requester) = track.requester {
+            let name = guild.members.get(&requester).map_or_else(
+                || "unknown".to_string(),
+                |member| member.display_name().to_string(),
+            );
+
+            details.push(format!("requested by {name}"));
+        }
+
+        details.join(" | ")
+    }
+
+    pub fn format_position_bar(position: Duration, length: Duration) -> String {
+        const BAR_LENGTH: u64 = 20;
+
+        let filled = if length.as_secs() == 0 {
+            0
+        } else {
+            (position.as_secs() * BAR_LENGTH / length.as_secs()).min(BAR_LENGTH - 1)
+        };
+
+        (0..BAR_LENGTH)
+            .map(|idx| if idx == filled { '🔘' } else { '▬' })
+            .collect()
+    }
+
+    /// Parses `ss`, `mm:ss` or `hh:mm:ss` into a duration.
+    pub fn parse_timestamp(timestamp: &str) -> Option<Duration> {
+        let parts: Vec<&str> = timestamp.trim().split(':').collect();
+
+        if parts.len() > 3 {
+            return None;
+        }
+
+        let mut seconds: u64 = 0;
+
+        for (idx, part) in parts.into_iter().enumerate() {
+            let value = part.parse::<u64>().ok()?;
+
+            // Only the leading part may go past 59, `90` is fine but `1:90` is not
+            if idx > 0 && value >= 60 {
+                return None;
+            }
+
+            seconds = seconds.checked_mul(60)?.checked_add(value)?;
+        }
+
+        Some(Duration::from_secs(seconds))
+    }
+
+    /// Parses queue indices like `3`, `3-10` or `2 5 7`, separated by spaces or commas.
+    pub fn parse_indices(indices: &str) -> Option<Vec<RangeInclusive<usize>>> {
+        let mut ranges = Vec::new();
+
+        for part in indices
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|part| !part.is_empty())
+        {
+            let range = match part.split_once('-') {
+                Some((start, end)) => {
+                    let start = start.trim().parse().ok()?;
+                    let end = end.trim().parse().ok()?;
+
+                    if start > end {
+                        return None;
+                    }
+
+                    start..=end
+                }
+                None => {
+                    let index = part.parse().ok()?;
+                    index..=index
+                }
+            };
+
+            ranges.push(range);
+        }
+
+        if ranges.is_empty() {
+            None
+        } else {
+            Some(ranges)
+        }
+    }
+
     pub async fn formatted_song_listing(
         title: &str,
         track: &TrackHandle,
@@ -84,4 +208,35 @@ mod util {
         }
         Ok(response)
     }
+
+    #[cfg(test)]
+    mod tests {
+        use std::time::Duration;
+
+        use super::{parse_indices, parse_timestamp};
+
+        #[test]
+        fn test_parse_timestamp() {
+            assert_eq!(parse_timestamp("45"), Some(Duration::from_secs(45)));
+            assert_eq!(parse_timestamp("1:30"), Some(Duration::from_secs(90)));
+            assert_eq!(parse_timestamp("1:02:03"), Some(Duration::from_secs(3723)));
+            assert_eq!(parse_timestamp("1:2:3:4"), None);
+            assert_eq!(parse_timestamp("1:xx"), None);
+            assert_eq!(parse_timestamp("90"), Some(Duration::from_secs(90)));
+            assert_eq!(parse_timestamp("1:99:99"), None);
+            assert_eq!(parse_timestamp("1:60"), None);
+            assert_eq!(parse_timestamp(&format!("{}:00:00", u64::MAX)), None);
+        }
+
+        #[test]
+        fn test_parse_indices() {
+            assert_eq!(parse_indices("3"), Some(vec![3..=3]));
+            assert_eq!(parse_indices("3-10"), Some(vec![3..=10]));
+            assert_eq!(parse_indices("2 5, 7"), Some(vec![2..=2, 5..=5, 7..=7]));
+            assert_eq!(parse_indices("1-3 8"), Some(vec![1..=3, 8..=8]));
+            assert_eq!(parse_indices("10-3"), None);
+            assert_eq!(parse_indices("two"), None);
+            assert_eq!(parse_indices(""), None);
+        }
+    }
 }
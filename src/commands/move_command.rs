@@ -0,0 +1,128 @@
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::prelude::*,
+    prelude::*,
+};
+
+use crate::{checks::*, invoker::Invoker, queue::get_queue_from_ctx_and_guild_id};
+
+#[command("move")]
+#[aliases("mv")]
+#[checks(dj_only)]
+#[description = "Moves a song to another spot in the queue, use ~queue to see what index to use"]
+#[usage = "<index of song to move> <index to move it to>"]
+#[bucket = "global"]
+async fn move_command(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let from = args.single::<usize>()?;
+    let to = args.single::<usize>()?;
+
+    move_track(ctx, msg.into(), from, to).await
+}
+
+#[command]
+#[checks(dj_only)]
+#[description = "Swaps two songs in the queue, use ~queue to see what index to use"]
+#[usage = "<index of song> <index of other song>"]
+#[bucket = "global"]
+async fn swap(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let first = args.single::<usize>()?;
+    let second = args.single::<usize>()?;
+
+    swap_tracks(ctx, msg.into(), first, second).await
+}
+
+pub async fn move_track(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    from: usize,
+    to: usize,
+) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let manager = songbird::get(ctx).await.unwrap().clone();
+
+    if manager.get(guild_id).is_some() {
+        let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
+
+        if from == 0 || to == 0 {
+            invoker
+                .reply(ctx, "The currently playing song can't be moved")
+                .await?;
+            return Ok(());
+        }
+
+        match queue.move_track(from, to) {
+            Some(track) => {
+                invoker
+                    .say(
+                        ctx,
+                        format!(
+                            "Moved `{}` to position {}",
+                            track.display_title(),
+                            to.min(queue.len() - 1)
+                        ),
+                    )
+                    .await?;
+            }
+            None => {
+                invoker
+                    .reply(ctx, format!("There is no song at index: {from}"))
+                    .await?;
+            }
+        }
+    } else {
+        invoker.reply(ctx, "Nothing playing").await?;
+    }
+
+    Ok(())
+}
+
+pub async fn swap_tracks(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    first: usize,
+    second: usize,
+) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let manager = songbird::get(ctx).await.unwrap().clone();
+
+    if manager.get(guild_id).is_some() {
+        let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
+
+        if first == 0 || second == 0 {
+            invoker
+                .reply(ctx, "The currently playing song can't be moved")
+                .await?;
+            return Ok(());
+        }
+
+        let len = queue.len();
+
+        if first >= len || second >= len {
+            invoker
+                .reply(
+                    ctx,
+                    format!("There is no song at index: {}", first.max(second)),
+                )
+                .await?;
+            return Ok(());
+        }
+
+        let (first_title, second_title) = queue.modify_queue(|queue| {
+            queue.swap(first, second);
+            (
+                queue[first].display_title().to_string(),
+                queue[second].display_title().to_string(),
+            )
+        });
+
+        invoker
+            .say(ctx, format!("Swapped `{second_title}` and `{first_title}`"))
+            .await?;
+    } else {
+        invoker.reply(ctx, "Nothing playing").await?;
+    }
+
+    Ok(())
+}
@@ -4,21 +4,25 @@ use serenity::{
     prelude::*,
 };
 
-use crate::checks::*;
+use crate::{checks::*, invoker::Invoker};
 
 #[command]
 #[checks(dj_only)]
 #[description = "Mutes the bot"]
 #[bucket = "global"]
 async fn mute(ctx: &Context, msg: &Message) -> CommandResult {
-    let guild_id = msg.guild_id.unwrap();
+    toggle_mute(ctx, msg.into()).await
+}
+
+pub async fn toggle_mute(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild_id = invoker.guild_id();
 
     let manager = songbird::get(ctx).await.unwrap().clone();
 
     let handler_lock = match manager.get(guild_id) {
         Some(handler) => handler,
         None => {
-            msg.reply_ping(ctx, "Not in a voice channel").await?;
+            invoker.reply(ctx, "Not in a voice channel").await?;
 
             return Ok(());
         }
@@ -28,10 +32,10 @@ async fn mute(ctx: &Context, msg: &Message) -> CommandResult {
 
     if handler.is_mute() {
         handler.mute(false).await?;
-        msg.channel_id.say(ctx, "Unmuted").await?;
+        invoker.say(ctx, "Unmuted").await?;
     } else {
         handler.mute(true).await?;
-        msg.channel_id.say(ctx, "Muted").await?;
+        invoker.say(ctx, "Muted").await?;
     }
 
     Ok(())
@@ -0,0 +1,66 @@
+use serenity::{
+    framework::standard::{macros::command, CommandResult},
+    model::prelude::*,
+    prelude::*,
+};
+
+use crate::{
+    checks::*,
+    data::PoolContainer,
+    db::{get_normalise, set_normalise},
+    invoker::Invoker,
+    queue::QueueMap,
+};
+
+#[command]
+#[aliases("normalize", "loudnorm")]
+#[checks(admin_only)]
+#[description = "Enables/Disables loudness normalisation, which keeps every song at about the same volume"]
+#[bucket = "global"]
+async fn normalise(ctx: &Context, msg: &Message) -> CommandResult {
+    toggle_normalise(ctx, msg.into()).await
+}
+
+pub async fn toggle_normalise(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let queue = {
+        let data = ctx.data.read().await;
+        let pool = data.get::<PoolContainer>().unwrap();
+
+        let normalise = !get_normalise(pool, guild_id.into()).await?;
+
+        set_normalise(pool, guild_id.into(), normalise).await?;
+
+        if normalise {
+            invoker
+                .say(
+                    ctx,
+                    "Enabled loudness normalisation, songs now play at about the same volume",
+                )
+                .await?;
+        } else {
+            invoker.say(ctx, "Disabled loudness normalisation").await?;
+        }
+
+        let queue_container = data.get::<QueueMap>().unwrap().clone();
+        let queue = queue_container.get(&guild_id).map(|queue| queue.clone());
+
+        if let Some(queue) = &queue {
+            queue.set_normalise(normalise);
+        }
+
+        queue
+    };
+
+    let manager = songbird::get(ctx).await.unwrap().clone();
+
+    // The current song is reloaded so the change can be heard right away
+    if let (Some(queue), Some(handler_lock)) = (queue, manager.get(guild_id)) {
+        queue
+            .reload_current(handler_lock, invoker.channel_id(), ctx.http.clone())
+            .await?;
+    }
+
+    Ok(())
+}
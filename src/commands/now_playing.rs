@@ -4,9 +4,11 @@ use serenity::{
     prelude::*,
 };
 
-use crate::{checks::*, queue::get_queue_from_ctx_and_guild_id};
+use crate::{
+    checks::*, filters::format_filters, invoker::Invoker, queue::get_queue_from_ctx_and_guild_id,
+};
 
-use super::util::formatted_song_listing;
+use super::util::{formatted_song_listing, track_details};
 
 #[command]
 #[aliases("np", "playing")]
@@ -14,7 +16,11 @@ use super::util::formatted_song_listing;
 #[description = "Shows the currently playing track"]
 #[bucket = "global"]
 async fn now_playing(ctx: &Context, msg: &Message) -> CommandResult {
-    let guild_id = msg.guild_id.unwrap();
+    show_now_playing(ctx, msg.into()).await
+}
+
+pub async fn show_now_playing(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild_id = invoker.guild_id();
 
     let manager = songbird::get(ctx).await.unwrap().clone();
 
@@ -24,18 +30,45 @@ async fn now_playing(ctx: &Context, msg: &Message) -> CommandResult {
 
         if let Some(current_track) = current {
             let metadata = current_track.metadata();
-            let title = metadata.title.clone().unwrap();
+            let title = metadata.title.clone().unwrap_or_default();
+
+            let mut response =
+                formatted_song_listing(&title, &current_track, true, false, None).await?;
+
+            response.push_line("");
+
+            if let Some(queued_track) = queue.find(current_track.uuid()) {
+                let guild = invoker.guild(ctx).await;
+
+                response.push_line(track_details(&queued_track, &guild));
+
+                if let Some(stream_title) = &queued_track.stream_title {
+                    response.push_line(format!("On air: {stream_title}"));
+                }
+
+                if let Some(url) = &queued_track.url {
+                    response.push_line(format!("<{url}>"));
+                }
+            }
+
+            response
+                .push_line("")
+                .push_bold_line(format!("Repeat: {}", queue.repeat_mode()));
+
+            let filters = queue.filters();
+
+            if !filters.is_empty() {
+                response.push_bold_line(format!("Filters: {}", format_filters(&filters)));
+            }
 
-            let response = formatted_song_listing(&title, &current_track, true, false, None)
-                .await?
-                .build();
+            let response = response.build();
 
-            msg.channel_id.say(ctx, response).await?;
+            invoker.say(ctx, response).await?;
         } else {
-            msg.channel_id.say(ctx, "No track playing").await?;
+            invoker.say(ctx, "No track playing").await?;
         }
     } else {
-        msg.reply(ctx, "Nothing playing").await?;
+        invoker.reply(ctx, "Nothing playing").await?;
     }
 
     Ok(())
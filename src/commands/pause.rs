@@ -5,14 +5,23 @@ use serenity::{
 };
 use songbird::tracks::PlayMode;
 
-use crate::{checks::*, queue::get_queue_from_ctx_and_guild_id};
+use crate::{
+    checks::*,
+    fades::{fade_in_and_play, fade_out_and_pause},
+    invoker::Invoker,
+    queue::get_queue_from_ctx_and_guild_id,
+};
 
 #[command]
 #[checks(dj_only)]
 #[description = "Pauses/Resumes the currently playing track"]
 #[bucket = "global"]
 async fn pause(ctx: &Context, msg: &Message) -> CommandResult {
-    let guild_id = msg.guild_id.unwrap();
+    toggle_pause(ctx, msg.into()).await
+}
+
+pub async fn toggle_pause(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild_id = invoker.guild_id();
 
     let manager = songbird::get(ctx).await.unwrap().clone();
 
@@ -24,19 +33,19 @@ async fn pause(ctx: &Context, msg: &Message) -> CommandResult {
         if let Some(handle) = current {
             match handle.get_info().await?.playing {
                 PlayMode::Play => {
-                    handle.pause()?;
-                    msg.channel_id.say(ctx, "Paused").await?;
+                    fade_out_and_pause(&handle).await?;
+                    invoker.say(ctx, "Paused").await?;
                 }
                 PlayMode::Pause => {
-                    handle.play()?;
-                    msg.channel_id.say(ctx, "Resumed").await?;
+                    fade_in_and_play(&handle).await?;
+                    invoker.say(ctx, "Resumed").await?;
                 }
                 _ => {
-                    msg.channel_id.say(ctx, "Nothing playing").await?;
+                    invoker.say(ctx, "Nothing playing").await?;
                 }
             }
         } else {
-            msg.channel_id.say(ctx, "Nothing playing").await?;
+            invoker.say(ctx, "Nothing playing").await?;
         }
     }
 
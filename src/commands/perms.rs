@@ -14,6 +14,7 @@ use crate::{
     consts::INSUFFICIENT_PERMISSIONS_MESSAGE,
     data::PoolContainer,
     db::{delete_user, get_all_users_with_perm, get_user_perms, set_user_perms, UserPerm},
+    invoker::Invoker,
 };
 
 async fn args_to_user(
@@ -99,30 +100,39 @@ async fn perms(ctx: &Context, msg: &Message) -> CommandResult {
 #[usage = "<perm level>"]
 #[bucket = "global"]
 async fn list(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let level = match args.single_quoted::<String>() {
-        Ok(level) => level,
-        Err(_) => {
-            msg.reply_ping(ctx, "Please provide a permission you would like to list the members of, available options are: `admin`, `dj`, and `blacklist`").await?;
+    list_perms(ctx, msg.into(), args.single_quoted::<String>().ok()).await
+}
+
+pub async fn list_perms(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    level: Option<String>,
+) -> CommandResult {
+    let level = match level {
+        Some(level) => level,
+        None => {
+            invoker.reply(ctx, "Please provide a permission you would like to list the members of, available options are: `admin`, `dj`, and `blacklist`").await?;
             return Ok(());
         }
     };
 
     match level.to_lowercase().as_ref() {
         "admin" => {
-            list_users_with_perm(ctx, msg, UserPerm::Admin).await?;
+            list_users_with_perm(ctx, invoker, UserPerm::Admin).await?;
         }
         "dj" => {
-            list_users_with_perm(ctx, msg, UserPerm::Dj).await?;
+            list_users_with_perm(ctx, invoker, UserPerm::Dj).await?;
         }
         "blacklist" => {
-            list_users_with_perm(ctx, msg, UserPerm::Blacklisted).await?;
+            list_users_with_perm(ctx, invoker, UserPerm::Blacklisted).await?;
         }
         _ => {
-            msg.reply_ping(
-                ctx,
-                "Not a valid permission, options are: `admin`, `dj`, and `blacklist`",
-            )
-            .await?;
+            invoker
+                .reply(
+                    ctx,
+                    "Not a valid permission, options are: `admin`, `dj`, and `blacklist`",
+                )
+                .await?;
         }
     };
 
@@ -131,35 +141,33 @@ async fn list(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
 
 async fn list_users_with_perm(
     ctx: &Context,
-    msg: &Message,
+    invoker: Invoker<'_>,
     perm_level: UserPerm,
 ) -> anyhow::Result<()> {
     let data = ctx.data.read().await;
     let pool = data.get::<PoolContainer>().unwrap();
 
-    let guild_id = msg.guild_id.unwrap();
+    let guild_id = invoker.guild_id();
 
     let returned_users = get_all_users_with_perm(pool, guild_id.into(), perm_level).await?;
 
     if returned_users.is_empty() {
-        msg.channel_id
+        invoker
             .say(ctx, format!("No users with {perm_level:?} role"))
             .await?;
     } else {
-        msg.channel_id
-            .send_message(ctx, |m| {
-                m.embed(|e| {
-                    e.title(format!("Users with {perm_level:?} permission"));
-                    let mut user_list = String::new();
-                    for user in returned_users {
-                        let user = UserId(user.user_id.try_into().unwrap());
-                        user_list.push_str(&format!("{}\n", user.mention()));
-                    }
-
-                    e.description(user_list);
-
-                    e
-                })
+        invoker
+            .send_embed(ctx, |e| {
+                e.title(format!("Users with {perm_level:?} permission"));
+                let mut user_list = String::new();
+                for user in returned_users {
+                    let user = UserId(user.user_id.try_into().unwrap());
+                    user_list.push_str(&format!("{}\n", user.mention()));
+                }
+
+                e.description(user_list);
+
+                e
             })
             .await?;
     }
@@ -178,42 +186,52 @@ async fn set(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
         None => return Ok(()),
     };
 
-    let perm_level = match args.single_quoted::<String>() {
-        Ok(level) => level,
-        Err(_) => {
-            msg.reply_ping(ctx, "Please provide a permission you would like to set the member to, available options are: `admin`, `dj`, `user`, and `blacklist`").await?;
+    set_perms(ctx, msg.into(), user, args.single_quoted::<String>().ok()).await
+}
+
+pub async fn set_perms(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    user: User,
+    perm_level: Option<String>,
+) -> CommandResult {
+    let perm_level = match perm_level {
+        Some(level) => level,
+        None => {
+            invoker.reply(ctx, "Please provide a permission you would like to set the member to, available options are: `admin`, `dj`, `user`, and `blacklist`").await?;
             return Ok(());
         }
     };
 
     match perm_level.to_lowercase().as_ref() {
         "admin" => {
-            set_user_perm_from_command(ctx, msg, UserPerm::Admin, user).await?;
+            set_user_perm_from_command(ctx, invoker, UserPerm::Admin, user).await?;
         }
         "dj" => {
-            set_user_perm_from_command(ctx, msg, UserPerm::Dj, user).await?;
+            set_user_perm_from_command(ctx, invoker, UserPerm::Dj, user).await?;
         }
         "user" => {
             let data = ctx.data.read().await;
             let pool = data.get::<PoolContainer>().unwrap();
 
-            let guild_id = msg.guild_id.unwrap();
+            let guild_id = invoker.guild_id();
 
             delete_user(pool, guild_id.into(), user.id.try_into().unwrap()).await?;
 
-            msg.channel_id
+            invoker
                 .say(ctx, format!("Set {}'s permission to User", user.mention()))
                 .await?;
         }
         "blacklist" => {
-            set_user_perm_from_command(ctx, msg, UserPerm::Blacklisted, user).await?;
+            set_user_perm_from_command(ctx, invoker, UserPerm::Blacklisted, user).await?;
         }
         _ => {
-            msg.reply_ping(
-                ctx,
-                "Not a valid permission, options are `admin`, `dj`, `user`, and `blacklist`",
-            )
-            .await?;
+            invoker
+                .reply(
+                    ctx,
+                    "Not a valid permission, options are `admin`, `dj`, `user`, and `blacklist`",
+                )
+                .await?;
         }
     }
 
@@ -222,14 +240,14 @@ async fn set(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
 
 async fn set_user_perm_from_command(
     ctx: &Context,
-    msg: &Message,
+    invoker: Invoker<'_>,
     perm_level: UserPerm,
     user: User,
 ) -> anyhow::Result<()> {
     let data = ctx.data.read().await;
     let pool = data.get::<PoolContainer>().unwrap();
 
-    let guild_id = msg.guild_id.unwrap();
+    let guild_id = invoker.guild_id();
 
     let user_perm = set_user_perms(
         pool,
@@ -239,7 +257,7 @@ async fn set_user_perm_from_command(
     )
     .await?;
 
-    msg.channel_id
+    invoker
         .say(
             ctx,
             format!("Set {}'s permission to {:?}", user.mention(), user_perm),
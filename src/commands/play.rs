@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
@@ -7,15 +7,23 @@ use serenity::{
     utils::Color,
 };
 
-use songbird::Event;
-
-use uuid::Uuid;
+use songbird::{Call, Event};
 
+use super::{favourites::play_favourites, util::format_duration_to_mm_ss};
 use crate::{
     checks::*,
-    data::ReqwestClientContainer,
-    playlists::{get_list_of_spotify_tracks, get_list_of_urls, get_ytdl_metadata},
-    queue::{get_queue_from_ctx_and_guild_id, QueueMap, QueuedTrack},
+    data::{PoolContainer, ReqwestClientContainer},
+    db::get_queue_limits,
+    invoker::Invoker,
+    library::{is_audio_attachment, probe_duration},
+    playlists::{
+        get_list_of_urls, get_start_time_from_url, get_ytdl_metadata, parse_music_link,
+        resolve_music_link, UnsupportedLink,
+    },
+    queue::{get_queue_from_ctx_and_guild_id, Queue, QueueMap, QueuedTrack},
+    queue_limits::{LimitExceeded, QueueLimits},
+    queue_settings::load_queue_settings,
+    radio::probe_radio_stream,
     voice_events::ChannelIdleChecker,
 };
 
@@ -23,13 +31,293 @@ use crate::{
 #[aliases("p")]
 #[checks(dj_only)]
 #[description = "Adds a new song to the queue, can either be the name of a song, or a link to it"]
-#[usage = "<name or url of song>"]
+#[usage = "<name or url of song, or favourites to add your favourites, or attach audio files>"]
 #[bucket = "global"]
 async fn play(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
-    let url = match args.remains() {
-        Some(url) => url.to_string(),
+    if msg
+        .attachments
+        .iter()
+        .any(|attachment| is_audio_attachment(&attachment.url))
+    {
+        return play_attachments(ctx, msg.into(), &msg.attachments).await;
+    }
+
+    play_track(ctx, msg.into(), args.remains().map(|url| url.to_string())).await
+}
+
+/// Adds the audio files attached to the message, other attachments are ignored.
+pub async fn play_attachments(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    attachments: &[Attachment],
+) -> CommandResult {
+    let handler_lock = match get_or_join_handler(ctx, invoker).await? {
+        Some(handler_lock) => handler_lock,
+        None => return Ok(()),
+    };
+
+    let mut tracks = Vec::new();
+
+    for attachment in attachments
+        .iter()
+        .filter(|attachment| is_audio_attachment(&attachment.url))
+    {
+        tracks.push(QueuedTrack {
+            title: Some(attachment.filename.clone()),
+            url: Some(attachment.url.clone()),
+            // Needed for the limit on track lengths
+            duration: probe_duration(&attachment.url).await,
+            ..QueuedTrack::new(attachment.url.clone(), Some(invoker.author_id()))
+        });
+    }
+
+    let queue = get_queue_from_ctx_and_guild_id(ctx, invoker.guild_id()).await;
+
+    let count = add_tracks_within_limits(ctx, invoker, &queue, handler_lock, tracks).await?;
+
+    invoker
+        .say(ctx, format!("Added {count} attached songs to the queue"))
+        .await?;
+
+    Ok(())
+}
+
+/// Returns the call the bot is in, or joins the author's voice channel.
+/// When it could not join the author is told why and `None` is returned.
+pub async fn get_or_join_handler(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+) -> anyhow::Result<Option<Arc<Mutex<Call>>>> {
+    let guild = invoker.guild(ctx).await;
+    let guild_id = guild.id;
+
+    let manager = songbird::get(ctx).await.unwrap().clone();
+
+    if let Some(handler_lock) = manager.get(guild_id) {
+        return Ok(Some(handler_lock));
+    }
+
+    let channel_id = guild
+        .voice_states
+        .get(&invoker.author_id())
+        .and_then(|voice_state| voice_state.channel_id);
+
+    let connect_to = match channel_id {
+        Some(c) => c,
+        None => {
+            invoker.say(ctx, "Not in a channel to join into").await?;
+
+            return Ok(None);
+        }
+    };
+
+    let (handler_lock, success) = manager.join(guild_id, connect_to).await;
+
+    if success.is_ok() {
+        let mut handler = handler_lock.lock().await;
+        let data = ctx.data.read().await;
+        let queue_container = data.get::<QueueMap>().unwrap().clone();
+        let queue = queue_container.entry(guild_id).or_default().clone();
+
+        handler.add_global_event(
+            Event::Periodic(Duration::from_secs(60), None),
+            ChannelIdleChecker {
+                handler_lock: handler_lock.clone(),
+                elapsed: Default::default(),
+                guild_id,
+                chan_id: invoker.channel_id(),
+                http: ctx.http.clone(),
+                cache: ctx.cache.clone(),
+                queue: queue.clone(),
+            },
+        );
+
+        let pool = data.get::<PoolContainer>().unwrap();
+        load_queue_settings(pool, guild_id, &queue).await?;
+
+        invoker
+            .say(ctx, format!("Joined {}", connect_to.mention()))
+            .await?;
+    } else {
+        invoker
+            .say(ctx, "There was an error joining the channel")
+            .await?;
+        return Ok(None);
+    }
+
+    Ok(Some(handler_lock))
+}
+
+/// The queue limits of the guild, DJs and admins are exempt from them unless the guild turned that off.
+pub async fn get_queue_limits_for(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+) -> anyhow::Result<QueueLimits> {
+    let guild_id = invoker.guild_id();
+
+    let limits = {
+        let data = ctx.data.read().await;
+        let pool = data.get::<PoolContainer>().unwrap();
+
+        get_queue_limits(pool, guild_id.into()).await?
+    };
+
+    if limits.exempt_djs && is_dj_or_admin(ctx, guild_id, invoker.author_id()).await {
+        return Ok(QueueLimits::default());
+    }
+
+    Ok(limits)
+}
+
+/// Checks if the user can add a track of the given length to the queue.
+pub fn check_queue_limits(
+    limits: &QueueLimits,
+    queue: &Queue,
+    user_id: UserId,
+    track_length: Option<Duration>,
+) -> Result<(), LimitExceeded> {
+    let tracks = queue.current_queue();
+    let user_tracks = tracks
+        .iter()
+        .filter(|track| track.requester == Some(user_id))
+        .count();
+
+    limits.check(tracks.len(), user_tracks, track_length)
+}
+
+/// Adds the tracks in order until a limit is reached, tracks that are too long are left out.
+/// Returns how many of the tracks were added.
+pub async fn add_tracks_within_limits(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    queue: &Queue,
+    handler_lock: Arc<Mutex<Call>>,
+    tracks: Vec<QueuedTrack>,
+) -> anyhow::Result<usize> {
+    let limits = get_queue_limits_for(ctx, invoker).await?;
+    let max_tracks = limits
+        .max_playlist_size
+        .map_or(usize::MAX, |max| max as usize);
+
+    if tracks.len() > max_tracks {
+        invoker
+            .say(ctx, format!("Only the first {max_tracks} songs are added"))
+            .await?;
+    }
+
+    let mut added = 0;
+    let mut too_long = 0;
+
+    for track in tracks.into_iter().take(max_tracks) {
+        let guild = invoker.guild(ctx).await;
+
+        // Stop adding once the bot was disconnected, the queue is gone by then
+        if guild
+            .voice_states
+            .get(&ctx.cache.current_user_id().await)
+            .is_none()
+        {
+            queue.stop();
+            break;
+        }
+
+        match check_queue_limits(&limits, queue, invoker.author_id(), track.duration) {
+            Ok(()) => {}
+            Err(LimitExceeded::TrackLength(_)) => {
+                too_long += 1;
+                continue;
+            }
+            Err(e) => {
+                invoker.reply(ctx, e).await?;
+                break;
+            }
+        }
+
+        queue
+            .add(
+                track,
+                handler_lock.clone(),
+                invoker.channel_id(),
+                ctx.http.clone(),
+            )
+            .await?;
+
+        added += 1;
+    }
+
+    if too_long > 0 {
+        invoker
+            .say(ctx, format!("Skipped {too_long} songs that were too long"))
+            .await?;
+    }
+
+    Ok(added)
+}
+
+/// Only looked up when there is a limit on track lengths, as it takes about as long as loading the track.
+pub async fn track_length_for_limits(limits: &QueueLimits, search: &str) -> Option<Duration> {
+    if limits.max_track_length.is_none() {
+        return None;
+    }
+
+    get_ytdl_metadata(search)
+        .await
+        .ok()
+        .and_then(|metadata| metadata.duration)
+        .map(Duration::from_secs_f32)
+}
+
+/// Adds a radio station or another endless stream, it plays until it is skipped.
+pub async fn add_live_stream(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    handler_lock: Arc<Mutex<Call>>,
+    url: String,
+    title: String,
+) -> CommandResult {
+    let limits = get_queue_limits_for(ctx, invoker).await?;
+    let queue = get_queue_from_ctx_and_guild_id(ctx, invoker.guild_id()).await;
+
+    if let Err(e) = check_queue_limits(&limits, &queue, invoker.author_id(), None) {
+        invoker.reply(ctx, e).await?;
+        return Ok(());
+    }
+
+    queue
+        .add(
+            QueuedTrack {
+                title: Some(title.clone()),
+                url: Some(url.clone()),
+                live: true,
+                radio: true,
+                ..QueuedTrack::new(url, Some(invoker.author_id()))
+            },
+            handler_lock,
+            invoker.channel_id(),
+            ctx.http.clone(),
+        )
+        .await?;
+
+    invoker
+        .say(
+            ctx,
+            format!("Added `{title}` to the queue, it plays until it is skipped"),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// The length shown when a song is added, live streams have none.
+fn format_added_length(length: Option<Duration>) -> String {
+    length.map_or_else(|| "LIVE".to_string(), format_duration_to_mm_ss)
+}
+
+pub async fn play_track(ctx: &Context, invoker: Invoker<'_>, url: Option<String>) -> CommandResult {
+    let url = match url {
+        Some(url) => url,
         None => {
-            msg.channel_id
+            invoker
                 .say(
                     ctx,
                     "Must provide a url to video or audio, or the name of a song",
@@ -40,307 +328,251 @@ async fn play(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
         }
     };
 
-    let guild = msg.guild(ctx).await.unwrap();
-    let guild_id = guild.id;
+    if url.eq_ignore_ascii_case("favourites") {
+        return play_favourites(ctx, invoker).await;
+    }
 
-    let manager = songbird::get(ctx).await.unwrap().clone();
-    let handler_lock = {
-        let is_in_channel = manager.get(guild_id);
+    let guild_id = invoker.guild_id();
 
-        if let Some(handler_lock) = is_in_channel {
-            handler_lock
-        } else {
-            let channel_id = guild
-                .voice_states
-                .get(&msg.author.id)
-                .and_then(|voice_state| voice_state.channel_id);
+    let handler_lock = match get_or_join_handler(ctx, invoker).await? {
+        Some(handler_lock) => handler_lock,
+        None => return Ok(()),
+    };
 
-            let connect_to = match channel_id {
-                Some(c) => c,
-                None => {
-                    msg.channel_id
-                        .say(ctx, "Not in a channel to join into")
-                        .await?;
+    let limits = get_queue_limits_for(ctx, invoker).await?;
 
+    if let Some(link) = parse_music_link(&url) {
+        let description = link.description();
+
+        let mut reply_msg = invoker
+            .send_embed(ctx, |e| e.title(format!("Downloading {description}...")))
+            .await?;
+
+        let tracks = {
+            let data = ctx.data.read().await;
+            let client = data.get::<ReqwestClientContainer>().unwrap().clone();
+            resolve_music_link(&client, link).await
+        };
+
+        let tracks = match tracks {
+            Ok(tracks) => tracks,
+            Err(e) => match e.downcast::<UnsupportedLink>() {
+                Ok(unsupported) => {
+                    invoker.reply(ctx, unsupported).await?;
                     return Ok(());
                 }
-            };
+                Err(e) => return Err(e.into()),
+            },
+        };
+
+        let tracks = tracks
+            .into_iter()
+            .map(|track| QueuedTrack {
+                title: track.title,
+                duration: track.duration,
+                ..QueuedTrack::new(track.name, Some(invoker.author_id()))
+            })
+            .collect();
 
-            let (handler_lock, success) = manager.join(guild_id, connect_to).await;
-
-            if success.is_ok() {
-                let mut handler = handler_lock.lock().await;
-                let data = ctx.data.read().await;
-                let queue_container = data.get::<QueueMap>().unwrap().clone();
-                let queue = queue_container.entry(guild_id).or_default();
-
-                handler.add_global_event(
-                    Event::Periodic(Duration::from_secs(60), None),
-                    ChannelIdleChecker {
-                        handler_lock: handler_lock.clone(),
-                        elapsed: Default::default(),
-                        guild_id,
-                        chan_id: msg.channel_id,
-                        http: ctx.http.clone(),
-                        cache: ctx.cache.clone(),
-                        queue: queue.clone(),
-                    },
-                );
-
-                msg.channel_id
-                    .say(ctx, format!("Joined {}", connect_to.mention()))
-                    .await?;
-            } else {
-                msg.channel_id
-                    .say(ctx, "There was an error joining the channel")
-                    .await?;
-                return Ok(());
-            }
+        let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
 
-            handler_lock
-        }
-    };
+        add_tracks_within_limits(ctx, invoker, &queue, handler_lock, tracks).await?;
 
-    if url.starts_with("http") {
+        invoker
+            .edit_embed(ctx, &mut reply_msg, |e| {
+                e.title(format!("Finished downloading {description}"))
+            })
+            .await?;
+    } else if url.starts_with("http") {
         if url.contains("list=") {
-            let mut reply_msg = msg
-                .channel_id
-                .send_message(ctx, |m| m.embed(|e| e.title("Downloading playlist...")))
+            let mut reply_msg = invoker
+                .send_embed(ctx, |e| e.title("Downloading playlist..."))
                 .await?;
 
-            let urls = get_list_of_urls(&url).await?;
-
-            for url in urls {
-                let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
+            let tracks = get_list_of_urls(&url)
+                .await?
+                .into_iter()
+                .map(|mut url| QueuedTrack {
+                    url: url.url,
+                    duration: url.duration.map(Duration::from_secs_f64),
+                    uploader: url.uploader,
+                    // The last thumbnail yt-dlp lists is the largest one
+                    thumbnail: url.thumbnails.pop().map(|thumbnail| thumbnail.url),
+                    ..QueuedTrack::new(url.title, Some(invoker.author_id()))
+                })
+                .collect();
 
-                let guild = msg.guild(ctx).await.unwrap();
+            let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
 
-                if guild
-                    .voice_states
-                    .get(&ctx.cache.current_user_id().await)
-                    .is_none()
-                {
-                    queue.stop();
-                    break;
-                }
-                queue
-                    .add(
-                        QueuedTrack {
-                            name: url.title,
-                            uuid: Uuid::new_v4(),
-                        },
-                        handler_lock.clone(),
-                        msg.channel_id,
-                        ctx.http.clone(),
-                    )
-                    .await?;
-            }
+            add_tracks_within_limits(ctx, invoker, &queue, handler_lock, tracks).await?;
 
-            reply_msg
-                .edit(ctx, |m| {
-                    m.embed(|e| e.title("Finished downloading playlist"))
+            invoker
+                .edit_embed(ctx, &mut reply_msg, |e| {
+                    e.title("Finished downloading playlist")
                 })
                 .await?;
-        } else if url.starts_with("https://open.spotify.com/playlist/") {
-            let mut reply_msg = msg
-                .channel_id
-                .send_message(ctx, |m| {
-                    m.embed(|e| e.title("Downloading spotify playlist..."))
-                })
-                .await?;
-
-            let url = url
-                .strip_prefix("https://open.spotify.com/playlist/")
-                .unwrap();
+        } else if let Some(radio) = probe_radio_stream(&url).await {
+            let title = radio.name.unwrap_or_else(|| url.clone());
 
-            let url = url.split('?').take(1).collect::<Vec<_>>()[0];
-
-            let tracks = {
-                let data = ctx.data.read().await;
-                let client = data.get::<ReqwestClientContainer>().unwrap().clone();
-                get_list_of_spotify_tracks(client, url).await?
-            };
-
-            for item in tracks.items {
-                let track = item.track;
-                let formatted_search = format!("{} {}", track.name, track.artists[0].name);
-
-                let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
+            add_live_stream(ctx, invoker, handler_lock, url, title).await?;
+        } else {
+            let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
 
-                let guild = msg.guild(ctx).await.unwrap();
+            let duration = track_length_for_limits(&limits, &url).await;
 
-                if guild
-                    .voice_states
-                    .get(&ctx.cache.current_user_id().await)
-                    .is_none()
-                {
-                    queue.stop();
-                    break;
-                }
-                queue
-                    .add(
-                        QueuedTrack {
-                            name: formatted_search,
-                            uuid: Uuid::new_v4(),
-                        },
-                        handler_lock.clone(),
-                        msg.channel_id,
-                        ctx.http.clone(),
-                    )
-                    .await?;
+            if let Err(e) = check_queue_limits(&limits, &queue, invoker.author_id(), duration) {
+                invoker.reply(ctx, e).await?;
+                return Ok(());
             }
 
-            reply_msg
-                .edit(ctx, |m| {
-                    m.embed(|e| e.title("Finished downloading spotify playlist"))
-                })
-                .await?;
-        } else {
-            let mut reply_msg = msg
-                .channel_id
-                .send_message(ctx, |m| m.embed(|e| e.title("Downloading song...")))
+            let mut reply_msg = invoker
+                .send_embed(ctx, |e| e.title("Downloading song..."))
                 .await?;
 
-            let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
-
             queue
                 .add(
                     QueuedTrack {
-                        name: url,
-                        uuid: Uuid::new_v4(),
+                        start_time: get_start_time_from_url(&url),
+                        ..QueuedTrack::new(url, Some(invoker.author_id()))
                     },
                     handler_lock,
-                    msg.channel_id,
+                    invoker.channel_id(),
                     ctx.http.clone(),
                 )
                 .await?;
 
-            reply_msg
-                .edit(ctx, |m| m.embed(|e| e.title("Added song to queue")))
+            invoker
+                .edit_embed(ctx, &mut reply_msg, |e| e.title("Added song to queue"))
                 .await?;
         }
     } else {
-        let mut reply_msg = msg
-            .channel_id
-            .send_message(ctx, |m| {
-                m.embed(|e| {
-                    e.title("Searching for song...")
-                        .description(format!("Searching for `{url}`"))
-                })
+        let mut reply_msg = invoker
+            .send_embed(ctx, |e| {
+                e.title("Searching for song...")
+                    .description(format!("Searching for `{url}`"))
             })
             .await?;
 
         let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
 
         if queue.current().lock().is_none() {
+            let duration = track_length_for_limits(&limits, &url).await;
+
+            if let Err(e) = check_queue_limits(&limits, &queue, invoker.author_id(), duration) {
+                invoker.reply(ctx, e).await?;
+                return Ok(());
+            }
+
             queue
                 .add(
-                    QueuedTrack {
-                        name: url,
-                        uuid: Uuid::new_v4(),
-                    },
+                    QueuedTrack::new(url, Some(invoker.author_id())),
                     handler_lock,
-                    msg.channel_id,
+                    invoker.channel_id(),
                     ctx.http.clone(),
                 )
                 .await?;
 
-            let track_metadata = queue.current().lock().as_ref().unwrap().metadata().clone();
-
-            reply_msg
-                .edit(ctx, |m| {
-                    m.embed(|e| {
-                        let title = track_metadata.title.unwrap();
-                        let artist = track_metadata.channel.unwrap();
-                        let length = track_metadata.duration.unwrap();
-                        let mut seconds = (length.as_secs() % 60).to_string();
-                        let minutes = (length.as_secs() / 60) % 60;
-                        let url = track_metadata.source_url.unwrap();
-                        let hours = (length.as_secs() / 60) / 60;
-
-                        if seconds.len() < 2 {
-                            seconds = format!("0{seconds}");
-                        }
-
-                        e.title(format!("Added song: {title}"));
-                        e.fields(vec![
-                            ("Title:", format!("[{title}]({url})"), true),
-                            ("Artist", artist, true),
-                            (
-                                "Spot in queue",
-                                (queue.len()).to_string(),
-                                true,
-                            ),
-                            ("Length", format!("{hours}:{minutes}:{seconds}"), true),
-                        ]);
-
-                        e.footer(|f| {
-                            f.icon_url("https://avatars0.githubusercontent.com/u/35662205?s=460&u=a154620c136da5ad4acc9c473864cc6349a4e874&v=4");
-                            f.text("If you like my work consider donating, ~donate");
-
-                            f
-                        });
-
-                        e.color(Color::DARK_GREEN);
-
-                        e
-                    })
+            let current = { queue.current().lock().clone() };
+
+            let track_metadata = match current {
+                Some(handle) => handle.metadata().clone(),
+                None => {
+                    invoker
+                        .edit_embed(ctx, &mut reply_msg, |e| e.title("Could not play that song"))
+                        .await?;
+                    return Ok(());
+                }
+            };
+
+            invoker
+                .edit_embed(ctx, &mut reply_msg, |e| {
+                    let title = track_metadata.title.unwrap_or_default();
+                    let artist = track_metadata.channel.unwrap_or_default();
+                    let length = format_added_length(track_metadata.duration);
+                    let url = track_metadata.source_url.unwrap_or_default();
+
+                    e.title(format!("Added song: {title}"));
+                    e.fields(vec![
+                        ("Title:", format!("[{title}]({url})"), true),
+                        ("Artist", artist, true),
+                        (
+                            "Spot in queue",
+                            (queue.len()).to_string(),
+                            true,
+                        ),
+                        ("Length", length, true),
+                    ]);
+
+                    e.footer(|f| {
+                        f.icon_url("https://avatars0.githubusercontent.com/u/35662205?s=460&u=a154620c136da5ad4acc9c473864cc6349a4e874&v=4");
+                        f.text("If you like my work consider donating, ~donate");
+
+                        f
+                    });
+
+                    e.color(Color::DARK_GREEN);
+
+                    e
                 })
                 .await?;
         } else {
+            let track_metadata = get_ytdl_metadata(&url).await?;
+
+            let duration = track_metadata.duration.map(Duration::from_secs_f32);
+
+            if let Err(e) = check_queue_limits(&limits, &queue, invoker.author_id(), duration) {
+                invoker.reply(ctx, e).await?;
+                return Ok(());
+            }
+
             queue
                 .add(
                     QueuedTrack {
-                        name: url.clone(),
-                        uuid: Uuid::new_v4(),
+                        title: Some(track_metadata.title.clone()),
+                        url: Some(track_metadata.webpage_url.clone()),
+                        duration,
+                        uploader: Some(track_metadata.uploader.clone()),
+                        thumbnail: track_metadata.thumbnail.clone(),
+                        live: track_metadata.is_live.unwrap_or_default() || duration.is_none(),
+                        ..QueuedTrack::new(url, Some(invoker.author_id()))
                     },
                     handler_lock,
-                    msg.channel_id,
+                    invoker.channel_id(),
                     ctx.http.clone(),
                 )
                 .await?;
-            let track_metadata = get_ytdl_metadata(&url).await?;
-
-            reply_msg
-        .edit(ctx, |m| {
-            m.embed(|e| {
-                let title = track_metadata.title;
-                let artist = track_metadata.uploader;
-                let length = Duration::from_secs(track_metadata.duration as u64);
-                let mut seconds = (length.as_secs() % 60).to_string();
-                let minutes = (length.as_secs() / 60) % 60;
-                let url = track_metadata.webpage_url;
-                let hours = (length.as_secs() / 60) / 60;
-
-                if seconds.len() < 2 {
-                    seconds = format!("0{seconds}");
-                }
 
-                e.title(format!("Added song: {title}"));
-                e.fields(vec![
-                    ("Title:", format!("[{title}]({url})"), true),
-                    ("Artist", artist, true),
-                    (
-                        "Spot in queue",
-                        (queue.len()).to_string(),
-                        true,
-                    ),
-                    ("Length", format!("{hours}:{minutes}:{seconds}"), true),
-                ]);
-
-                e.footer(|f| {
-                    f.icon_url("https://avatars0.githubusercontent.com/u/35662205?s=460&u=a154620c136da5ad4acc9c473864cc6349a4e874&v=4");
-                    f.text("If you like my work consider donating, ~donate");
-
-                    f
-                });
-
-                e.color(Color::DARK_GREEN);
-
-                e
-            })
-        })
-        .await?;
+            invoker
+                .edit_embed(ctx, &mut reply_msg, |e| {
+                    let title = track_metadata.title;
+                    let artist = track_metadata.uploader;
+                    let length = format_added_length(duration);
+                    let url = track_metadata.webpage_url;
+
+                    e.title(format!("Added song: {title}"));
+                    e.fields(vec![
+                        ("Title:", format!("[{title}]({url})"), true),
+                        ("Artist", artist, true),
+                        (
+                            "Spot in queue",
+                            (queue.len()).to_string(),
+                            true,
+                        ),
+                        ("Length", length, true),
+                    ]);
+
+                    e.footer(|f| {
+                        f.icon_url("https://avatars0.githubusercontent.com/u/35662205?s=460&u=a154620c136da5ad4acc9c473864cc6349a4e874&v=4");
+                        f.text("If you like my work consider donating, ~donate");
+
+                        f
+                    });
+
+                    e.color(Color::DARK_GREEN);
+
+                    e
+                })
+                .await?;
         }
     };
 
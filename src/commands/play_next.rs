@@ -0,0 +1,131 @@
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::prelude::*,
+    prelude::*,
+};
+
+use super::play::{check_queue_limits, get_queue_limits_for, play_track, track_length_for_limits};
+use crate::{
+    checks::*,
+    data::ReqwestClientContainer,
+    invoker::Invoker,
+    playlists::{
+        get_start_time_from_url, parse_music_link, resolve_music_link, LinkKind, UnsupportedLink,
+    },
+    queue::{get_queue_from_ctx_and_guild_id, QueuedTrack},
+};
+
+#[command("playnext")]
+#[aliases("pn")]
+#[checks(dj_only)]
+#[description = "Adds a song to the front of the queue, so it plays after the current one"]
+#[usage = "<name or url of song>"]
+#[bucket = "global"]
+async fn play_next(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    play_track_next(ctx, msg.into(), args.remains().map(|url| url.to_string())).await
+}
+
+pub async fn play_track_next(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    url: Option<String>,
+) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let manager = songbird::get(ctx).await.unwrap().clone();
+
+    // Without anything playing this is the same as a normal play
+    let handler_lock = match manager.get(guild_id) {
+        Some(handler_lock) => handler_lock,
+        None => return play_track(ctx, invoker, url).await,
+    };
+
+    let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
+
+    if queue.len() < 2 {
+        return play_track(ctx, invoker, url).await;
+    }
+
+    let url = match url {
+        Some(url) => url,
+        None => {
+            invoker
+                .say(
+                    ctx,
+                    "Must provide a url to video or audio, or the name of a song",
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let music_link = parse_music_link(&url);
+
+    if url.contains("list=") || matches!(music_link, Some(link) if link.kind != LinkKind::Song) {
+        invoker
+            .reply(ctx, "Playlists can only be added to the end of the queue")
+            .await?;
+        return Ok(());
+    }
+
+    let track = match music_link {
+        Some(link) => {
+            let tracks = {
+                let data = ctx.data.read().await;
+                let client = data.get::<ReqwestClientContainer>().unwrap().clone();
+                resolve_music_link(&client, link).await
+            };
+
+            let tracks = match tracks {
+                Ok(tracks) => tracks,
+                Err(e) => match e.downcast::<UnsupportedLink>() {
+                    Ok(unsupported) => {
+                        invoker.reply(ctx, unsupported).await?;
+                        return Ok(());
+                    }
+                    Err(e) => return Err(e.into()),
+                },
+            };
+
+            match tracks.into_iter().next() {
+                Some(track) => QueuedTrack {
+                    title: track.title,
+                    duration: track.duration,
+                    ..QueuedTrack::new(track.name, Some(invoker.author_id()))
+                },
+                None => {
+                    invoker
+                        .reply(ctx, format!("Could not find that {}", link.description()))
+                        .await?;
+                    return Ok(());
+                }
+            }
+        }
+        None => QueuedTrack {
+            start_time: get_start_time_from_url(&url),
+            ..QueuedTrack::new(url, Some(invoker.author_id()))
+        },
+    };
+
+    let limits = get_queue_limits_for(ctx, invoker).await?;
+    let track_length = match track.duration {
+        Some(duration) => Some(duration),
+        None => track_length_for_limits(&limits, &track.name).await,
+    };
+
+    if let Err(e) = check_queue_limits(&limits, &queue, invoker.author_id(), track_length) {
+        invoker.reply(ctx, e).await?;
+        return Ok(());
+    }
+
+    let title = track.display_title().to_string();
+
+    queue
+        .add_next(track, handler_lock, invoker.channel_id(), ctx.http.clone())
+        .await?;
+
+    invoker.say(ctx, format!("Playing `{title}` next")).await?;
+
+    Ok(())
+}
@@ -0,0 +1,605 @@
+use std::{
+    convert::{TryFrom, TryInto},
+    time::Duration,
+};
+
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::prelude::*,
+    prelude::*,
+    utils::Color,
+};
+
+use super::{
+    play::{add_tracks_within_limits, get_or_join_handler},
+    util::format_duration_to_mm_ss,
+};
+use crate::{
+    checks::*,
+    data::PoolContainer,
+    db::{
+        add_playlist_tracks, create_playlist, delete_playlist, find_playlist, get_playlist_tracks,
+        get_playlists, remove_playlist_track, set_playlist_shared, set_playlist_tracks, Playlist,
+        PlaylistTrack,
+    },
+    invoker::Invoker,
+    playlists::get_start_time_from_url,
+    queue::{get_existing_queue, get_queue_from_ctx_and_guild_id, QueuedTrack},
+};
+
+const MAX_NAME_LENGTH: usize = 100;
+
+fn to_playlist_track(track: QueuedTrack) -> PlaylistTrack {
+    PlaylistTrack {
+        name: track.name,
+        title: track.title,
+        url: track.url,
+        duration_ms: track
+            .duration
+            .map(|duration| i64::try_from(duration.as_millis()).unwrap_or_default()),
+    }
+}
+
+fn from_playlist_track(track: PlaylistTrack, requester: UserId) -> QueuedTrack {
+    QueuedTrack {
+        start_time: get_start_time_from_url(&track.name),
+        title: track.title,
+        url: track.url,
+        duration: track
+            .duration_ms
+            .and_then(|duration_ms| duration_ms.try_into().ok())
+            .map(Duration::from_millis),
+        ..QueuedTrack::new(track.name, Some(requester))
+    }
+}
+
+#[command]
+#[aliases("pl")]
+#[checks(not_blacklisted)]
+#[description = "Lists your playlists and the ones shared with the server, use the sub commands to manage them"]
+#[sub_commands(
+    playlist_create,
+    playlist_add,
+    playlist_remove,
+    playlist_show,
+    playlist_play,
+    playlist_delete,
+    playlist_save,
+    playlist_share
+)]
+#[bucket = "global"]
+async fn playlist(ctx: &Context, msg: &Message) -> CommandResult {
+    show_playlist(ctx, msg.into(), None).await
+}
+
+#[command("create")]
+#[checks(not_blacklisted)]
+#[description = "Creates an empty playlist, add shared to let everyone in the server play it"]
+#[usage = "<name> <shared>"]
+#[bucket = "global"]
+async fn playlist_create(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let name = args.single_quoted::<String>()?;
+    let shared = args.single::<String>().map_or(false, |arg| arg == "shared");
+
+    create_user_playlist(ctx, msg.into(), &name, shared).await
+}
+
+#[command("add")]
+#[checks(not_blacklisted)]
+#[description = "Adds a song to a playlist, without a song the currently playing one is added"]
+#[usage = "<name> <name or url of song>"]
+#[bucket = "global"]
+async fn playlist_add(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let name = args.single_quoted::<String>()?;
+    let song = args.remains().map(|song| song.to_string());
+
+    add_to_playlist(ctx, msg.into(), &name, song).await
+}
+
+#[command("remove")]
+#[checks(not_blacklisted)]
+#[description = "Removes a song from a playlist, use ~playlist show to see what index to use"]
+#[usage = "<name> <index of song>"]
+#[bucket = "global"]
+async fn playlist_remove(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let name = args.single_quoted::<String>()?;
+    let index = args.single::<usize>()?;
+
+    remove_from_playlist(ctx, msg.into(), &name, index).await
+}
+
+#[command("show")]
+#[checks(not_blacklisted)]
+#[description = "Shows the songs in a playlist"]
+#[usage = "<name>"]
+#[bucket = "global"]
+async fn playlist_show(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let name = args.single_quoted::<String>().ok();
+
+    show_playlist(ctx, msg.into(), name).await
+}
+
+#[command("play")]
+#[checks(dj_only)]
+#[description = "Adds every song of a playlist to the queue"]
+#[usage = "<name>"]
+#[bucket = "global"]
+async fn playlist_play(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let name = args.single_quoted::<String>()?;
+
+    play_playlist(ctx, msg.into(), &name).await
+}
+
+#[command("delete")]
+#[checks(not_blacklisted)]
+#[description = "Deletes one of your playlists"]
+#[usage = "<name>"]
+#[bucket = "global"]
+async fn playlist_delete(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let name = args.single_quoted::<String>()?;
+
+    delete_user_playlist(ctx, msg.into(), &name).await
+}
+
+#[command("save")]
+#[checks(not_blacklisted)]
+#[description = "Saves the queue as a playlist, replacing the songs if you already have one with that name"]
+#[usage = "<name> <shared>"]
+#[bucket = "global"]
+async fn playlist_save(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let name = args.single_quoted::<String>()?;
+    let shared = args.single::<String>().map_or(false, |arg| arg == "shared");
+
+    save_queue_as_playlist(ctx, msg.into(), &name, shared).await
+}
+
+#[command("share")]
+#[checks(not_blacklisted)]
+#[description = "Shares one of your playlists with the server, or makes it personal again"]
+#[usage = "<name>"]
+#[bucket = "global"]
+async fn playlist_share(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let name = args.single_quoted::<String>()?;
+
+    toggle_playlist_shared(ctx, msg.into(), &name).await
+}
+
+/// Finds a playlist that can be changed by the author, they are told why if there is none.
+async fn find_own_playlist(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    name: &str,
+) -> anyhow::Result<Option<Playlist>> {
+    let data = ctx.data.read().await;
+    let pool = data.get::<PoolContainer>().unwrap();
+
+    let playlist = find_playlist(
+        pool,
+        invoker.guild_id().into(),
+        invoker.author_id().into(),
+        name,
+    )
+    .await?;
+
+    match playlist {
+        Some(playlist) if playlist.owner_id == i64::from(invoker.author_id()) => Ok(Some(playlist)),
+        Some(_) => {
+            invoker
+                .reply(ctx, "Only the owner of a playlist can change it")
+                .await?;
+            Ok(None)
+        }
+        None => {
+            invoker
+                .reply(ctx, format!("There is no playlist called `{name}`"))
+                .await?;
+            Ok(None)
+        }
+    }
+}
+
+pub async fn create_user_playlist(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    name: &str,
+    shared: bool,
+) -> CommandResult {
+    if name.len() > MAX_NAME_LENGTH {
+        invoker
+            .reply(
+                ctx,
+                format!("The name has to be under {MAX_NAME_LENGTH} characters"),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let data = ctx.data.read().await;
+    let pool = data.get::<PoolContainer>().unwrap();
+
+    let playlist = create_playlist(
+        pool,
+        invoker.guild_id().into(),
+        invoker.author_id().into(),
+        name,
+        shared,
+    )
+    .await?;
+
+    if playlist.is_some() {
+        invoker
+            .say(ctx, format!("Created playlist `{name}`"))
+            .await?;
+    } else {
+        invoker
+            .reply(ctx, format!("You already have a playlist called `{name}`"))
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn add_to_playlist(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    name: &str,
+    song: Option<String>,
+) -> CommandResult {
+    let track = match song {
+        Some(song) => QueuedTrack::new(song, None),
+        None => {
+            let current = get_existing_queue(ctx, invoker.guild_id())
+                .await
+                .and_then(|queue| queue.current_queue().into_iter().next());
+
+            match current {
+                Some(track) => track,
+                None => {
+                    invoker
+                        .reply(ctx, "Nothing playing, include the song to add")
+                        .await?;
+                    return Ok(());
+                }
+            }
+        }
+    };
+
+    let playlist = match find_own_playlist(ctx, invoker, name).await? {
+        Some(playlist) => playlist,
+        None => return Ok(()),
+    };
+
+    let title = track.display_title().to_string();
+
+    let data = ctx.data.read().await;
+    let pool = data.get::<PoolContainer>().unwrap();
+
+    add_playlist_tracks(pool, playlist.playlist_id, &[to_playlist_track(track)]).await?;
+
+    invoker
+        .say(ctx, format!("Added `{title}` to `{}`", playlist.name))
+        .await?;
+
+    Ok(())
+}
+
+pub async fn remove_from_playlist(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    name: &str,
+    index: usize,
+) -> CommandResult {
+    let playlist = match find_own_playlist(ctx, invoker, name).await? {
+        Some(playlist) => playlist,
+        None => return Ok(()),
+    };
+
+    let data = ctx.data.read().await;
+    let pool = data.get::<PoolContainer>().unwrap();
+
+    let removed = match index.checked_sub(1) {
+        Some(index) => remove_playlist_track(pool, playlist.playlist_id, index.try_into()?).await?,
+        None => None,
+    };
+
+    match removed {
+        Some(track) => {
+            let title = track.title.unwrap_or(track.name);
+
+            invoker
+                .say(ctx, format!("Removed `{title}` from `{}`", playlist.name))
+                .await?;
+        }
+        None => {
+            invoker
+                .reply(ctx, format!("There is no song at index: {index}"))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Without a name every playlist the author can see is listed.
+pub async fn show_playlist(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    name: Option<String>,
+) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let data = ctx.data.read().await;
+    let pool = data.get::<PoolContainer>().unwrap();
+
+    let name = match name {
+        Some(name) => name,
+        None => {
+            let playlists =
+                get_playlists(pool, guild_id.into(), invoker.author_id().into()).await?;
+
+            if playlists.is_empty() {
+                invoker
+                    .say(
+                        ctx,
+                        "There are no playlists yet, make one with ~playlist create",
+                    )
+                    .await?;
+                return Ok(());
+            }
+
+            let description = playlists
+                .iter()
+                .map(|playlist| {
+                    let owner = UserId(playlist.owner_id.try_into().unwrap_or_default());
+
+                    if playlist.shared {
+                        format!("`{}` shared by {}", playlist.name, owner.mention())
+                    } else {
+                        format!("`{}` personal", playlist.name)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            invoker
+                .send_embed(ctx, |e| {
+                    e.title("Playlists")
+                        .description(description)
+                        .color(Color::DARK_GREEN)
+                })
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let playlist =
+        match find_playlist(pool, guild_id.into(), invoker.author_id().into(), &name).await? {
+            Some(playlist) => playlist,
+            None => {
+                invoker
+                    .reply(ctx, format!("There is no playlist called `{name}`"))
+                    .await?;
+                return Ok(());
+            }
+        };
+
+    let tracks = get_playlist_tracks(pool, playlist.playlist_id).await?;
+
+    let total_length: i64 = tracks.iter().filter_map(|track| track.duration_ms).sum();
+
+    let mut description = tracks
+        .iter()
+        .take(25)
+        .enumerate()
+        .map(|(idx, track)| {
+            format!(
+                "`{}` {}",
+                idx + 1,
+                track.title.as_deref().unwrap_or(&track.name)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if tracks.len() > 25 {
+        description.push_str(&format!("\nand {} more", tracks.len() - 25));
+    }
+
+    if tracks.is_empty() {
+        description.push_str("This playlist is empty");
+    }
+
+    invoker
+        .send_embed(ctx, |e| {
+            e.title(&playlist.name)
+                .description(description)
+                .footer(|f| {
+                    f.text(format!(
+                        "{} songs, {} | {}",
+                        tracks.len(),
+                        format_duration_to_mm_ss(Duration::from_millis(
+                            total_length.try_into().unwrap_or_default()
+                        )),
+                        if playlist.shared {
+                            "Shared with the server"
+                        } else {
+                            "Personal"
+                        }
+                    ))
+                })
+                .color(Color::DARK_GREEN)
+        })
+        .await?;
+
+    Ok(())
+}
+
+pub async fn play_playlist(ctx: &Context, invoker: Invoker<'_>, name: &str) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let (playlist, tracks) = {
+        let data = ctx.data.read().await;
+        let pool = data.get::<PoolContainer>().unwrap();
+
+        match find_playlist(pool, guild_id.into(), invoker.author_id().into(), name).await? {
+            Some(playlist) => {
+                let tracks = get_playlist_tracks(pool, playlist.playlist_id).await?;
+                (playlist, tracks)
+            }
+            None => {
+                invoker
+                    .reply(ctx, format!("There is no playlist called `{name}`"))
+                    .await?;
+                return Ok(());
+            }
+        }
+    };
+
+    if tracks.is_empty() {
+        invoker
+            .reply(ctx, format!("`{}` is empty", playlist.name))
+            .await?;
+        return Ok(());
+    }
+
+    let handler_lock = match get_or_join_handler(ctx, invoker).await? {
+        Some(handler_lock) => handler_lock,
+        None => return Ok(()),
+    };
+
+    let mut reply_msg = invoker
+        .send_embed(ctx, |e| e.title(format!("Loading `{}`...", playlist.name)))
+        .await?;
+
+    let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
+    let tracks = tracks
+        .into_iter()
+        .map(|track| from_playlist_track(track, invoker.author_id()))
+        .collect();
+
+    let count = add_tracks_within_limits(ctx, invoker, &queue, handler_lock, tracks).await?;
+
+    invoker
+        .edit_embed(ctx, &mut reply_msg, |e| {
+            e.title(format!("Added {count} songs from `{}`", playlist.name))
+        })
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_user_playlist(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    name: &str,
+) -> CommandResult {
+    let playlist = match find_own_playlist(ctx, invoker, name).await? {
+        Some(playlist) => playlist,
+        None => return Ok(()),
+    };
+
+    let data = ctx.data.read().await;
+    let pool = data.get::<PoolContainer>().unwrap();
+
+    delete_playlist(pool, playlist.playlist_id).await?;
+
+    invoker
+        .say(ctx, format!("Deleted playlist `{}`", playlist.name))
+        .await?;
+
+    Ok(())
+}
+
+pub async fn save_queue_as_playlist(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    name: &str,
+    shared: bool,
+) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let tracks: Vec<PlaylistTrack> = match get_existing_queue(ctx, guild_id).await {
+        Some(queue) => queue
+            .current_queue()
+            .into_iter()
+            .map(to_playlist_track)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    if tracks.is_empty() {
+        invoker.reply(ctx, "The queue is empty").await?;
+        return Ok(());
+    }
+
+    if name.len() > MAX_NAME_LENGTH {
+        invoker
+            .reply(
+                ctx,
+                format!("The name has to be under {MAX_NAME_LENGTH} characters"),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let data = ctx.data.read().await;
+    let pool = data.get::<PoolContainer>().unwrap();
+
+    let author_id: i64 = invoker.author_id().into();
+
+    let playlist = match find_playlist(pool, guild_id.into(), author_id, name).await? {
+        Some(playlist) if playlist.owner_id == author_id => playlist,
+        _ => match create_playlist(pool, guild_id.into(), author_id, name, shared).await? {
+            Some(playlist) => playlist,
+            None => {
+                invoker
+                    .reply(ctx, format!("You already have a playlist called `{name}`"))
+                    .await?;
+                return Ok(());
+            }
+        },
+    };
+
+    set_playlist_tracks(pool, playlist.playlist_id, &tracks).await?;
+
+    invoker
+        .say(
+            ctx,
+            format!("Saved {} songs to `{}`", tracks.len(), playlist.name),
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn toggle_playlist_shared(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    name: &str,
+) -> CommandResult {
+    let playlist = match find_own_playlist(ctx, invoker, name).await? {
+        Some(playlist) => playlist,
+        None => return Ok(()),
+    };
+
+    let data = ctx.data.read().await;
+    let pool = data.get::<PoolContainer>().unwrap();
+
+    set_playlist_shared(pool, playlist.playlist_id, !playlist.shared).await?;
+
+    if playlist.shared {
+        invoker
+            .say(ctx, format!("`{}` is now personal", playlist.name))
+            .await?;
+    } else {
+        invoker
+            .say(
+                ctx,
+                format!("`{}` is now shared with the server", playlist.name),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
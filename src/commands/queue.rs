@@ -1,64 +1,292 @@
+use std::time::Duration;
+
 use serenity::{
-    framework::standard::{macros::command, CommandResult},
-    model::prelude::*,
+    builder::{CreateComponents, CreateEmbed},
+    framework::standard::{macros::command, Args, CommandResult, Reason},
+    model::{
+        interactions::{
+            message_component::{ButtonStyle, MessageComponentInteraction},
+            InteractionApplicationCommandCallbackDataFlags, InteractionResponseType,
+        },
+        prelude::*,
+    },
     prelude::*,
+    utils::Color,
+};
+use tracing::{info, warn};
+
+use super::util::{format_duration_to_mm_ss, format_track_length};
+use crate::{
+    checks::*,
+    consts::INSUFFICIENT_PERMISSIONS_MESSAGE,
+    invoker::Invoker,
+    queue::{get_queue_from_ctx_and_guild_id, Queue, QueueMap},
 };
 
-use super::util::formatted_song_listing;
-use crate::{checks::*, queue::get_queue_from_ctx_and_guild_id};
+const PAGE_SIZE: usize = 10;
+
+/// Followed by the page the button leads to.
+pub const QUEUE_PAGE_BUTTON: &str = "queue_page_";
 
 #[command]
 #[checks(not_blacklisted)]
 #[description = "Shows the currently queued tracks"]
+#[usage = "<page>"]
 #[bucket = "global"]
-async fn queue(ctx: &Context, msg: &Message) -> CommandResult {
-    let guild_id = msg.guild_id.unwrap();
+async fn queue(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let page = args.single::<usize>().ok();
 
-    let manager = songbird::get(ctx).await.unwrap().clone();
+    show_queue(ctx, msg.into(), page).await
+}
 
-    if manager.get(guild_id).is_some() {
-        let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
-        let current_queue = queue.current_queue();
+struct QueuePage {
+    description: String,
+    /// Starts at 0
+    page: usize,
+    pages: usize,
+}
 
-        if queue.is_empty() {
-            msg.channel_id.say(ctx, "The queue is empty").await?;
-            return Ok(());
-        }
+async fn build_queue_page(queue: &Queue, page: usize) -> Option<QueuePage> {
+    let tracks = queue.current_queue();
+    let current = { queue.current().lock().clone() };
 
-        let mut title_list = Vec::new();
+    if tracks.is_empty() {
+        return None;
+    }
 
-        for handle in current_queue {
-            let metadata = handle.name.clone();
+    let upcoming = tracks.len() - 1;
+    let pages = ((upcoming + PAGE_SIZE - 1) / PAGE_SIZE).max(1);
+    let page = page.min(pages - 1);
 
-            title_list.push(metadata);
-        }
+    let total_length: Duration = tracks.iter().filter_map(|track| track.duration).sum();
+    let unknown_lengths = tracks
+        .iter()
+        .filter(|track| track.duration.is_none() && !track.live)
+        .count();
+    let live_streams = tracks.iter().filter(|track| track.live).count();
+
+    let mut description = format!(
+        "**{} tracks | {}{}{} | Repeat: {}{}{}**\n\n",
+        tracks.len(),
+        format_duration_to_mm_ss(total_length),
+        if unknown_lengths > 0 {
+            format!(" + {unknown_lengths} of unknown length")
+        } else {
+            String::new()
+        },
+        if live_streams > 0 {
+            format!(" + {live_streams} live")
+        } else {
+            String::new()
+        },
+        queue.repeat_mode(),
+        if queue.fair_queue() {
+            " | Fair queue"
+        } else {
+            ""
+        },
+        if queue.autoplay() { " | Autoplay" } else { "" }
+    );
 
-        let current = { queue.current().lock().clone().unwrap() };
+    let state = match &current {
+        Some(handle) => handle.get_info().await.ok(),
+        None => None,
+    };
 
-        let metadata = current.metadata();
+    let now_playing = &tracks[0];
+    let position = state
+        .as_ref()
+        .map_or_else(Duration::default, |state| state.position);
 
-        let title = metadata.title.clone().unwrap();
+    description.push_str(&format!(
+        "Now playing: {} `{} / {}`\n",
+        track_link(now_playing.display_title(), now_playing.url.as_deref()),
+        format_duration_to_mm_ss(position),
+        format_track_length(now_playing)
+    ));
 
-        let mut response = formatted_song_listing(&title, &current, true, true, None).await?;
+    if let Some(stream_title) = &now_playing.stream_title {
+        description.push_str(&format!("On air: {stream_title}\n"));
+    }
 
-        title_list.remove(0);
+    description.push('\n');
 
-        for (idx, title) in title_list.iter().enumerate() {
-            response.push(format!("{title} "));
+    // Time until the next track starts, unknown as soon as one of the tracks has no length
+    let mut eta = now_playing
+        .duration
+        .map(|length| length.saturating_sub(position));
 
-            if idx != queue.len() {
-                response.push_mono(format!("{}", idx + 1)).push("\n\n");
-            } else {
-                response.push_mono(format!("{}", idx + 1));
+    for (idx, track) in tracks.iter().enumerate().skip(1) {
+        if idx > (page + 1) * PAGE_SIZE {
+            break;
+        }
+
+        if idx > page * PAGE_SIZE {
+            description.push_str(&format!(
+                "`{idx}` {} `{}`",
+                track_link(track.display_title(), track.url.as_deref()),
+                format_track_length(track)
+            ));
+
+            if let Some(requester) = track.requester {
+                description.push_str(&format!(" {}", requester.mention()));
             }
+
+            if let Some(eta) = eta {
+                description.push_str(&format!(" | in {}", format_duration_to_mm_ss(eta)));
+            }
+
+            description.push('\n');
         }
 
-        let response = response.build();
+        eta = eta.zip(track.duration).map(|(eta, length)| eta + length);
+    }
+
+    if upcoming == 0 {
+        description.push_str("Nothing else is queued");
+    }
+
+    Some(QueuePage {
+        description,
+        page,
+        pages,
+    })
+}
+
+fn track_link(title: &str, url: Option<&str>) -> String {
+    // Square brackets in titles would break the link
+    let title = title.replace('[', "(").replace(']', ")");
+
+    match url {
+        Some(url) => format!("[{title}]({url})"),
+        None => title,
+    }
+}
+
+fn queue_embed<'a>(e: &'a mut CreateEmbed, queue_page: &QueuePage) -> &'a mut CreateEmbed {
+    e.title("Queue")
+        .description(&queue_page.description)
+        .footer(|f| f.text(format!("Page {}/{}", queue_page.page + 1, queue_page.pages)))
+        .color(Color::DARK_GREEN)
+}
+
+fn queue_components<'a>(
+    c: &'a mut CreateComponents,
+    queue_page: &QueuePage,
+) -> &'a mut CreateComponents {
+    c.create_action_row(|r| {
+        r.create_button(|b| {
+            b.custom_id(format!(
+                "{QUEUE_PAGE_BUTTON}{}",
+                queue_page.page.saturating_sub(1)
+            ))
+            .style(ButtonStyle::Secondary)
+            .label("Previous")
+            .disabled(queue_page.page == 0)
+        })
+        .create_button(|b| {
+            b.custom_id(format!("{QUEUE_PAGE_BUTTON}{}", queue_page.page + 1))
+                .style(ButtonStyle::Secondary)
+                .label("Next")
+                .disabled(queue_page.page + 1 >= queue_page.pages)
+        })
+    })
+}
+
+/// Pages start at 1 for users.
+pub async fn show_queue(ctx: &Context, invoker: Invoker<'_>, page: Option<usize>) -> CommandResult {
+    let guild_id = invoker.guild_id();
 
-        msg.channel_id.say(ctx, response).await?;
+    let manager = songbird::get(ctx).await.unwrap().clone();
+
+    if manager.get(guild_id).is_some() {
+        let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
+
+        let page = page.unwrap_or(1).saturating_sub(1);
+
+        let queue_page = match build_queue_page(&queue, page).await {
+            Some(queue_page) => queue_page,
+            None => {
+                invoker.say(ctx, "The queue is empty").await?;
+                return Ok(());
+            }
+        };
+
+        invoker
+            .send_embed_with_components(
+                ctx,
+                |e| queue_embed(e, &queue_page),
+                |c| queue_components(c, &queue_page),
+            )
+            .await?;
     } else {
-        msg.reply_ping(ctx, "Nothing playing").await?;
+        invoker.reply(ctx, "Nothing playing").await?;
     }
 
     Ok(())
 }
+
+pub async fn handle_queue_page_button(ctx: &Context, component: &MessageComponentInteraction) {
+    let (guild_id, page) = match (
+        component.guild_id,
+        component
+            .data
+            .custom_id
+            .strip_prefix(QUEUE_PAGE_BUTTON)
+            .and_then(|page| page.parse::<usize>().ok()),
+    ) {
+        (Some(guild_id), Some(page)) => (guild_id, page),
+        _ => return,
+    };
+
+    if let Err(reason) = check_not_blacklisted(ctx, guild_id, component.user.id).await {
+        let content = match reason {
+            Reason::User(reason) => reason,
+            reason => {
+                info!("{:?}", reason);
+                INSUFFICIENT_PERMISSIONS_MESSAGE.to_string()
+            }
+        };
+
+        let _ = component
+            .create_interaction_response(ctx, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|d| {
+                        d.content(content)
+                            .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
+                    })
+            })
+            .await;
+        return;
+    }
+
+    let queue_container = {
+        let data = ctx.data.read().await;
+        data.get::<QueueMap>().unwrap().clone()
+    };
+
+    let queue = queue_container.get(&guild_id).map(|queue| queue.clone());
+
+    let queue_page = match queue {
+        Some(queue) => build_queue_page(&queue, page).await,
+        None => None,
+    };
+
+    let result = component
+        .create_interaction_response(ctx, |r| {
+            r.kind(InteractionResponseType::UpdateMessage)
+                .interaction_response_data(|d| match &queue_page {
+                    Some(queue_page) => d
+                        .create_embed(|e| queue_embed(e, queue_page))
+                        .components(|c| queue_components(c, queue_page)),
+                    None => d
+                        .create_embed(|e| e.title("The queue is empty").color(Color::DARK_GREEN))
+                        .components(|c| c),
+                })
+        })
+        .await;
+
+    if let Err(e) = result {
+        warn!("Could not change the queue page, {:?}", e);
+    }
+}
@@ -0,0 +1,147 @@
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::prelude::*,
+    prelude::*,
+    utils::Color,
+};
+
+use super::util::{format_duration_to_mm_ss, parse_timestamp};
+use crate::{
+    checks::*,
+    data::PoolContainer,
+    db::{get_queue_limits, set_queue_limits},
+    invoker::Invoker,
+    queue_limits::QueueLimits,
+};
+
+const USAGE: &str = "Please include the limit to change, `user`, `queue`, `length`, `playlist` or `exempt`, and a value, or `off` to remove the limit";
+
+#[command("queuelimits")]
+#[checks(admin_only)]
+#[description = "Shows or changes the limits on what users can add to the queue"]
+#[usage = "<user | queue | length | playlist | exempt> <number | length like 10:00 | on | off>"]
+#[bucket = "global"]
+async fn queue_limits(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let limit = args.single::<String>().ok();
+    let value = args.single::<String>().ok();
+
+    change_queue_limits(ctx, msg.into(), limit, value).await
+}
+
+fn format_count(count: Option<u32>) -> String {
+    count.map_or_else(|| "Unlimited".to_string(), |count| count.to_string())
+}
+
+/// `None` when the value could not be parsed, `Some(None)` when the limit is removed.
+fn parse_count(value: &str) -> Option<Option<u32>> {
+    match value.to_lowercase().as_ref() {
+        "off" | "none" => Some(None),
+        value => match value.parse() {
+            Ok(count) if count > 0 => Some(Some(count)),
+            _ => None,
+        },
+    }
+}
+
+pub async fn change_queue_limits(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    limit: Option<String>,
+    value: Option<String>,
+) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let data = ctx.data.read().await;
+    let pool = data.get::<PoolContainer>().unwrap();
+
+    let limits = get_queue_limits(pool, guild_id.into()).await?;
+
+    let (limit, value) = match (limit, value) {
+        (Some(limit), Some(value)) => (limit, value),
+        (None, _) => {
+            invoker
+                .send_embed(ctx, |e| {
+                    e.title("Queue limits")
+                        .field("Songs per user", format_count(limits.max_user_tracks), true)
+                        .field(
+                            "Songs in the queue",
+                            format_count(limits.max_queue_length),
+                            true,
+                        )
+                        .field(
+                            "Song length",
+                            limits
+                                .max_track_length
+                                .map_or_else(|| "Unlimited".to_string(), format_duration_to_mm_ss),
+                            true,
+                        )
+                        .field(
+                            "Songs per playlist",
+                            format_count(limits.max_playlist_size),
+                            true,
+                        )
+                        .field(
+                            "DJs and admins exempt",
+                            if limits.exempt_djs { "Yes" } else { "No" },
+                            true,
+                        )
+                        .color(Color::DARK_GREEN)
+                })
+                .await?;
+            return Ok(());
+        }
+        (Some(_), None) => {
+            invoker.reply(ctx, USAGE).await?;
+            return Ok(());
+        }
+    };
+
+    let updated = match limit.to_lowercase().as_ref() {
+        "user" => parse_count(&value).map(|max| QueueLimits {
+            max_user_tracks: max,
+            ..limits
+        }),
+        "queue" => parse_count(&value).map(|max| QueueLimits {
+            max_queue_length: max,
+            ..limits
+        }),
+        "length" => match value.to_lowercase().as_ref() {
+            "off" | "none" => Some(None),
+            value => parse_timestamp(value)
+                .filter(|length| !length.is_zero())
+                .map(Some),
+        }
+        .map(|max| QueueLimits {
+            max_track_length: max,
+            ..limits
+        }),
+        "playlist" => parse_count(&value).map(|max| QueueLimits {
+            max_playlist_size: max,
+            ..limits
+        }),
+        "exempt" => match value.to_lowercase().as_ref() {
+            "on" | "yes" | "true" => Some(true),
+            "off" | "no" | "false" => Some(false),
+            _ => None,
+        }
+        .map(|exempt_djs| QueueLimits {
+            exempt_djs,
+            ..limits
+        }),
+        _ => None,
+    };
+
+    let limits = match updated {
+        Some(limits) => limits,
+        None => {
+            invoker.reply(ctx, USAGE).await?;
+            return Ok(());
+        }
+    };
+
+    set_queue_limits(pool, guild_id.into(), limits).await?;
+
+    invoker.say(ctx, "Changed the queue limits").await?;
+
+    Ok(())
+}
@@ -1,45 +1,195 @@
+use std::collections::HashSet;
+
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::*,
     prelude::*,
+    utils::parse_mention,
+};
+
+use super::util::parse_indices;
+use crate::{
+    checks::*,
+    invoker::Invoker,
+    queue::{get_queue_from_ctx_and_guild_id, QueuedTrack},
 };
 
-use crate::{checks::*, queue::get_queue_from_ctx_and_guild_id};
+#[command]
+#[checks(dj_only)]
+#[description = "Removes songs from the queue, use ~queue to see what index to use"]
+#[usage = "<index of song to remove, a range like 3-10, or a list like 2 5 7>"]
+#[bucket = "global"]
+async fn remove(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let indices = args.remains().unwrap_or_default().to_string();
+
+    remove_tracks(ctx, msg.into(), &indices).await
+}
+
+#[command("removedupes")]
+#[checks(dj_only)]
+#[description = "Removes songs that are already in the queue"]
+#[bucket = "global"]
+async fn remove_dupes(ctx: &Context, msg: &Message) -> CommandResult {
+    remove_duplicate_tracks(ctx, msg.into()).await
+}
+
+#[command("removeuser")]
+#[checks(dj_only)]
+#[description = "Removes every song a user added to the queue"]
+#[usage = "<mention of user>"]
+#[bucket = "global"]
+async fn remove_user(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let user_id = match args.single_quoted::<String>().ok().and_then(parse_mention) {
+        Some(user_id) => UserId(user_id),
+        None => {
+            msg.reply_ping(ctx, "Please mention the user whose songs to remove")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    remove_user_tracks(ctx, msg.into(), user_id).await
+}
 
 #[command]
 #[checks(dj_only)]
-#[description = "Removes a song from the queue, use ~queue to see what index to use"]
-#[usage = "<index of song to remove>"]
+#[description = "Removes every upcoming song, but keeps the current one playing"]
 #[bucket = "global"]
-async fn remove(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let guild_id = msg.guild_id.unwrap();
-    let index = args.single_quoted::<usize>()?;
+async fn clear(ctx: &Context, msg: &Message) -> CommandResult {
+    clear_queue(ctx, msg.into()).await
+}
+
+async fn report_removed(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    removed: &[QueuedTrack],
+) -> CommandResult {
+    match removed {
+        [] => invoker.say(ctx, "No songs were removed").await?,
+        [track] => {
+            invoker
+                .say(ctx, format!("Removed song: `{}`", track.display_title()))
+                .await?
+        }
+        tracks => {
+            invoker
+                .say(ctx, format!("Removed {} songs", tracks.len()))
+                .await?
+        }
+    };
+
+    Ok(())
+}
+
+pub async fn remove_tracks(ctx: &Context, invoker: Invoker<'_>, indices: &str) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let ranges = match parse_indices(indices) {
+        Some(ranges) => ranges,
+        None => {
+            invoker
+                .reply(
+                    ctx,
+                    "Please include the index of the song, a range like 3-10, or a list like 2 5 7",
+                )
+                .await?;
+            return Ok(());
+        }
+    };
 
     let manager = songbird::get(ctx).await.unwrap().clone();
 
     if manager.get(guild_id).is_some() {
         let mut queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
         if !queue.is_empty() {
-            if index == 0 {
+            if ranges == [0..=0] {
                 queue.skip()?;
 
-                msg.channel_id.say(ctx, "Skipped the song").await?;
-            } else if index > queue.len() {
-                msg.reply_ping(ctx, format!("There is no song at index: {index}"))
+                invoker.say(ctx, "Skipped the song").await?;
+            } else if let Some(range) = ranges.iter().find(|range| *range.end() >= queue.len()) {
+                invoker
+                    .reply(ctx, format!("There is no song at index: {}", range.end()))
                     .await?;
                 return Ok(());
+            } else if ranges.len() == 1 && ranges[0].start() == ranges[0].end() {
+                let track = queue.dequeue(*ranges[0].start()).unwrap();
+                let title = track.display_title();
+
+                invoker.say(ctx, format!("Removed song: `{title}`")).await?;
             } else {
-                let track = queue.dequeue(index).unwrap();
-                let title = track.name;
+                let removed =
+                    queue.remove_upcoming(|idx, _| ranges.iter().any(|range| range.contains(&idx)));
 
-                msg.channel_id
-                    .say(ctx, format!("Removed song: `{title}`"))
-                    .await?;
+                report_removed(ctx, invoker, &removed).await?;
             }
         } else {
-            msg.channel_id.say(ctx, "The queue is empty").await?;
+            invoker.say(ctx, "The queue is empty").await?;
         }
     }
 
     Ok(())
 }
+
+pub async fn remove_duplicate_tracks(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let manager = songbird::get(ctx).await.unwrap().clone();
+
+    if manager.get(guild_id).is_some() {
+        let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
+        let mut seen = HashSet::new();
+
+        let removed = queue.remove_upcoming(|_, track| {
+            let key = track.url.as_ref().unwrap_or(&track.name).to_lowercase();
+            !seen.insert(key)
+        });
+
+        report_removed(ctx, invoker, &removed).await?;
+    } else {
+        invoker.reply(ctx, "Nothing playing").await?;
+    }
+
+    Ok(())
+}
+
+pub async fn remove_user_tracks(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    user_id: UserId,
+) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let manager = songbird::get(ctx).await.unwrap().clone();
+
+    if manager.get(guild_id).is_some() {
+        let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
+
+        let removed = queue.remove_upcoming(|_, track| track.requester == Some(user_id));
+
+        report_removed(ctx, invoker, &removed).await?;
+    } else {
+        invoker.reply(ctx, "Nothing playing").await?;
+    }
+
+    Ok(())
+}
+
+pub async fn clear_queue(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let manager = songbird::get(ctx).await.unwrap().clone();
+
+    if manager.get(guild_id).is_some() {
+        let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
+
+        let removed = queue.remove_upcoming(|_, _| true);
+
+        invoker
+            .say(ctx, format!("Cleared {} upcoming songs", removed.len()))
+            .await?;
+    } else {
+        invoker.reply(ctx, "Nothing playing").await?;
+    }
+
+    Ok(())
+}
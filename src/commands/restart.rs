@@ -6,14 +6,18 @@ use serenity::{
     prelude::*,
 };
 
-use crate::{checks::*, queue::get_queue_from_ctx_and_guild_id};
+use crate::{checks::*, invoker::Invoker, queue::get_queue_from_ctx_and_guild_id};
 
 #[command]
 #[checks(dj_only)]
 #[description = "Restarts the currently playing track"]
 #[bucket = "global"]
 async fn restart(ctx: &Context, msg: &Message) -> CommandResult {
-    let guild_id = msg.guild_id.unwrap();
+    restart_track(ctx, msg.into()).await
+}
+
+pub async fn restart_track(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild_id = invoker.guild_id();
 
     let manager = songbird::get(ctx).await.unwrap().clone();
 
@@ -23,13 +27,13 @@ async fn restart(ctx: &Context, msg: &Message) -> CommandResult {
         let current = { queue.current().lock().clone() };
 
         if let Some(track_handle) = current {
-            msg.channel_id.say(ctx, "Restarting track...").await?;
+            invoker.say(ctx, "Restarting track...").await?;
             track_handle.seek_time(Duration::from_secs(0))?;
         } else {
-            msg.reply_ping(ctx, "Nothing playing").await?;
+            invoker.reply(ctx, "Nothing playing").await?;
         }
     } else {
-        msg.reply_ping(ctx, "Not in a voice channel").await?;
+        invoker.reply(ctx, "Not in a voice channel").await?;
     }
 
     Ok(())
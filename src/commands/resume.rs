@@ -6,7 +6,9 @@ use serenity::{
 
 use songbird::tracks::PlayMode;
 
-use crate::{checks::*, queue::get_queue_from_ctx_and_guild_id};
+use crate::{
+    checks::*, fades::fade_in_and_play, invoker::Invoker, queue::get_queue_from_ctx_and_guild_id,
+};
 
 #[command]
 #[checks(dj_only)]
@@ -14,7 +16,11 @@ use crate::{checks::*, queue::get_queue_from_ctx_and_guild_id};
 #[aliases("unpause")]
 #[bucket = "global"]
 async fn resume(ctx: &Context, msg: &Message) -> CommandResult {
-    let guild_id = msg.guild_id.unwrap();
+    resume_track(ctx, msg.into()).await
+}
+
+pub async fn resume_track(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild_id = invoker.guild_id();
 
     let manager = songbird::get(ctx).await.unwrap().clone();
 
@@ -26,18 +32,18 @@ async fn resume(ctx: &Context, msg: &Message) -> CommandResult {
         if let Some(handle) = current {
             match handle.get_info().await?.playing {
                 PlayMode::Pause => {
-                    handle.play()?;
-                    msg.channel_id.say(ctx, "Resumed").await?;
+                    fade_in_and_play(&handle).await?;
+                    invoker.say(ctx, "Resumed").await?;
                 }
                 PlayMode::Play => {
-                    msg.channel_id.say(ctx, "Already playing").await?;
+                    invoker.say(ctx, "Already playing").await?;
                 }
                 _ => {
-                    msg.channel_id.say(ctx, "Nothing playing").await?;
+                    invoker.say(ctx, "Nothing playing").await?;
                 }
             }
         } else {
-            msg.channel_id.say(ctx, "Nothing playing").await?;
+            invoker.say(ctx, "Nothing playing").await?;
         }
     }
 
@@ -0,0 +1,285 @@
+use std::{sync::Arc, time::Duration};
+
+use dashmap::DashMap;
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::{
+        interactions::{
+            message_component::MessageComponentInteraction,
+            InteractionApplicationCommandCallbackDataFlags, InteractionResponseType,
+        },
+        prelude::*,
+    },
+    prelude::*,
+    utils::Color,
+};
+use tracing::warn;
+use uuid::Uuid;
+
+use super::{play::play_track, util::format_duration_to_mm_ss};
+use crate::{checks::*, invoker::Invoker, playlists::search_youtube};
+
+const SEARCH_RESULTS: usize = 5;
+const PICK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Followed by the id of the search the menu belongs to.
+pub const SEARCH_SELECT: &str = "search_select_";
+
+pub struct PendingSearch {
+    author_id: UserId,
+    results: Vec<SearchResult>,
+}
+
+struct SearchResult {
+    title: String,
+    url: String,
+}
+
+pub struct SearchResultsMap;
+
+impl TypeMapKey for SearchResultsMap {
+    type Value = Arc<DashMap<Uuid, PendingSearch>>;
+}
+
+#[command]
+#[checks(dj_only)]
+#[description = "Searches youtube and lets you pick which of the results to add to the queue"]
+#[usage = "<name of song>"]
+#[bucket = "global"]
+async fn search(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let query = args.remains().map(|query| query.to_string());
+
+    search_tracks(ctx, msg.into(), query).await
+}
+
+/// Discord limits the labels and descriptions of select menu options to 100 characters.
+fn truncate(text: &str) -> String {
+    text.chars().take(100).collect()
+}
+
+pub async fn search_tracks(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    query: Option<String>,
+) -> CommandResult {
+    let query = match query {
+        Some(query) => query,
+        None => {
+            invoker
+                .reply(ctx, "Must provide the name of a song to search for")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let results: Vec<_> = search_youtube(&query, SEARCH_RESULTS)
+        .await?
+        .into_iter()
+        .filter(|result| result.url.is_some())
+        .collect();
+
+    if results.is_empty() {
+        invoker
+            .reply(ctx, format!("No results for `{query}`"))
+            .await?;
+        return Ok(());
+    }
+
+    let details: Vec<String> = results
+        .iter()
+        .map(|result| {
+            let mut details = vec![result.duration.map_or_else(
+                || "?:??".to_string(),
+                |duration| format_duration_to_mm_ss(Duration::from_secs_f64(duration)),
+            )];
+
+            if let Some(uploader) = &result.uploader {
+                details.push(uploader.clone());
+            }
+
+            details.join(" | ")
+        })
+        .collect();
+
+    let description = results
+        .iter()
+        .zip(&details)
+        .enumerate()
+        .map(|(idx, (result, details))| {
+            format!(
+                "`{}` [{}]({})\n{details}",
+                idx + 1,
+                result.title,
+                result.url.as_deref().unwrap_or_default()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let search_id = Uuid::new_v4();
+
+    let message = invoker
+        .send_embed_with_components(
+            ctx,
+            |e| {
+                e.title(format!("Results for `{query}`"))
+                    .description(description)
+                    .footer(|f| {
+                        f.text(format!(
+                            "Pick a song within {} seconds",
+                            PICK_TIMEOUT.as_secs()
+                        ))
+                    })
+                    .color(Color::DARK_GREEN)
+            },
+            |c| {
+                c.create_action_row(|r| {
+                    r.create_select_menu(|m| {
+                        m.custom_id(format!("{SEARCH_SELECT}{search_id}"))
+                            .placeholder("Pick a song to add to the queue")
+                            .options(|o| {
+                                for (idx, (result, details)) in
+                                    results.iter().zip(&details).enumerate()
+                                {
+                                    o.create_option(|opt| {
+                                        opt.label(truncate(&format!(
+                                            "{}. {}",
+                                            idx + 1,
+                                            result.title
+                                        )))
+                                        .description(truncate(details))
+                                        .value(idx)
+                                    });
+                                }
+                                o
+                            })
+                    })
+                })
+            },
+        )
+        .await?;
+
+    let search_results = {
+        let data = ctx.data.read().await;
+        data.get::<SearchResultsMap>().unwrap().clone()
+    };
+
+    search_results.insert(
+        search_id,
+        PendingSearch {
+            author_id: invoker.author_id(),
+            results: results
+                .into_iter()
+                .filter_map(|result| {
+                    result.url.map(|url| SearchResult {
+                        title: result.title,
+                        url,
+                    })
+                })
+                .collect(),
+        },
+    );
+
+    let http = ctx.http.clone();
+
+    tokio::spawn(async move {
+        tokio::time::sleep(PICK_TIMEOUT).await;
+
+        // The search is only still pending when nothing was picked
+        if search_results.remove(&search_id).is_some() {
+            let result = message
+                .channel_id
+                .edit_message(&http, message.id, |m| {
+                    m.embed(|e| {
+                        e.title("Search timed out, nothing was picked")
+                            .color(Color::DARK_GREEN)
+                    })
+                    .components(|c| c)
+                })
+                .await;
+
+            if let Err(e) = result {
+                warn!("Could not close the search results, {:?}", e);
+            }
+        }
+    });
+
+    Ok(())
+}
+
+pub async fn handle_search_select(ctx: &Context, component: &MessageComponentInteraction) {
+    let search_id = match component
+        .data
+        .custom_id
+        .strip_prefix(SEARCH_SELECT)
+        .and_then(|search_id| search_id.parse::<Uuid>().ok())
+    {
+        Some(search_id) => search_id,
+        None => return,
+    };
+
+    let search_results = {
+        let data = ctx.data.read().await;
+        data.get::<SearchResultsMap>().unwrap().clone()
+    };
+
+    let content = match search_results.get(&search_id) {
+        Some(pending) if pending.author_id != component.user.id => {
+            Some("Only the person who searched can pick a song")
+        }
+        Some(_) => None,
+        None => Some("This search has timed out, search again to pick a song"),
+    };
+
+    if let Some(content) = content {
+        let _ = component
+            .create_interaction_response(ctx, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|d| {
+                        d.content(content)
+                            .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
+                    })
+            })
+            .await;
+        return;
+    }
+
+    let picked = search_results
+        .remove(&search_id)
+        .and_then(|(_, mut pending)| {
+            let idx = component.data.values.first()?.parse::<usize>().ok()?;
+
+            (idx < pending.results.len()).then(|| pending.results.swap_remove(idx))
+        });
+
+    let picked = match picked {
+        Some(picked) => picked,
+        None => return,
+    };
+
+    let result = component
+        .create_interaction_response(ctx, |r| {
+            r.kind(InteractionResponseType::UpdateMessage)
+                .interaction_response_data(|d| {
+                    d.create_embed(|e| {
+                        e.title("Picked song")
+                            .description(format!("[{}]({})", picked.title, picked.url))
+                            .color(Color::DARK_GREEN)
+                    })
+                    .components(|c| c)
+                })
+        })
+        .await;
+
+    if let Err(e) = result {
+        warn!("Could not respond to the search pick, {:?}", e);
+        return;
+    }
+
+    let invoker = Invoker::from(component);
+
+    if let Err(e) = play_track(ctx, invoker, Some(picked.url)).await {
+        warn!("Error adding the picked song, {:?}", e);
+        let _ = invoker.say(ctx, format!("Command returned an error, {e:?}, please report this on the support server https://discord.gg/5YytF9fPHr")).await;
+    }
+}
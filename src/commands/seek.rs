@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::prelude::*,
+    prelude::*,
+};
+
+use super::util::{format_duration_to_mm_ss, parse_timestamp};
+use crate::{checks::*, invoker::Invoker, queue::get_queue_from_ctx_and_guild_id};
+
+#[command]
+#[checks(dj_only)]
+#[description = "Seeks to a position in the currently playing track"]
+#[usage = "<mm:ss | hh:mm:ss>"]
+#[bucket = "global"]
+async fn seek(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    seek_to(ctx, msg.into(), args.single_quoted::<String>().ok()).await
+}
+
+#[command]
+#[aliases("ff", "fastforward")]
+#[checks(dj_only)]
+#[description = "Skips forward in the currently playing track"]
+#[usage = "<seconds>"]
+#[bucket = "global"]
+async fn forward(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    seek_forward(ctx, msg.into(), args.single_quoted::<u64>().ok()).await
+}
+
+#[command]
+#[aliases("rw")]
+#[checks(dj_only)]
+#[description = "Goes back in the currently playing track"]
+#[usage = "<seconds>"]
+#[bucket = "global"]
+async fn rewind(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    seek_backward(ctx, msg.into(), args.single_quoted::<u64>().ok()).await
+}
+
+pub async fn seek_to(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    timestamp: Option<String>,
+) -> CommandResult {
+    match timestamp.as_deref().and_then(parse_timestamp) {
+        Some(position) => seek_current_track(ctx, invoker, |_| position).await,
+        None => {
+            invoker
+                .reply(ctx, "Please provide a position like `mm:ss` or `hh:mm:ss`")
+                .await?;
+            Ok(())
+        }
+    }
+}
+
+pub async fn seek_forward(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    seconds: Option<u64>,
+) -> CommandResult {
+    match seconds {
+        Some(seconds) => {
+            seek_current_track(ctx, invoker, |position| {
+                position.saturating_add(Duration::from_secs(seconds))
+            })
+            .await
+        }
+        None => {
+            invoker
+                .reply(ctx, "Please provide the number of seconds to skip forward")
+                .await?;
+            Ok(())
+        }
+    }
+}
+
+pub async fn seek_backward(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    seconds: Option<u64>,
+) -> CommandResult {
+    match seconds {
+        Some(seconds) => {
+            seek_current_track(ctx, invoker, |position| {
+                position
+                    .checked_sub(Duration::from_secs(seconds))
+                    .unwrap_or_default()
+            })
+            .await
+        }
+        None => {
+            invoker
+                .reply(ctx, "Please provide the number of seconds to go back")
+                .await?;
+            Ok(())
+        }
+    }
+}
+
+async fn seek_current_track<F>(ctx: &Context, invoker: Invoker<'_>, target: F) -> CommandResult
+where
+    F: FnOnce(Duration) -> Duration,
+{
+    let guild_id = invoker.guild_id();
+
+    let manager = songbird::get(ctx).await.unwrap().clone();
+
+    if manager.get(guild_id).is_none() {
+        invoker.reply(ctx, "Not in a voice channel").await?;
+        return Ok(());
+    }
+
+    let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
+
+    let current = { queue.current().lock().clone() };
+
+    let handle = match current {
+        Some(handle) => handle,
+        None => {
+            invoker.reply(ctx, "Nothing playing").await?;
+            return Ok(());
+        }
+    };
+
+    // Speed filters make the position of the handle differ from the time in the track
+    let speed = queue.find(handle.uuid()).map_or(1.0, |track| track.speed);
+    let position = target(handle.get_info().await?.position.mul_f64(speed));
+
+    if let Some(length) = handle.metadata().duration {
+        if position >= length {
+            invoker
+                .reply(
+                    ctx,
+                    format!(
+                        "The track is only {} long",
+                        format_duration_to_mm_ss(length)
+                    ),
+                )
+                .await?;
+            return Ok(());
+        }
+    }
+
+    handle.seek_time(position.div_f64(speed))?;
+
+    invoker
+        .say(
+            ctx,
+            format!("Seeked to {}", format_duration_to_mm_ss(position)),
+        )
+        .await?;
+
+    Ok(())
+}
@@ -4,7 +4,7 @@ use serenity::{
     prelude::*,
 };
 
-use crate::{checks::*, queue::get_queue_from_ctx_and_guild_id};
+use crate::{checks::*, invoker::Invoker, queue::get_queue_from_ctx_and_guild_id};
 
 use std::collections::VecDeque;
 
@@ -39,7 +39,11 @@ impl<T> LenAndSwap for VecDeque<T> {
 #[description = "Shuffles the queue without changing the currently playing song"]
 #[bucket = "global"]
 async fn shuffle(ctx: &Context, msg: &Message) -> CommandResult {
-    let guild_id = msg.guild_id.unwrap();
+    shuffle_tracks(ctx, msg.into()).await
+}
+
+pub async fn shuffle_tracks(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild_id = invoker.guild_id();
 
     let manager = songbird::get(ctx).await.unwrap().clone();
 
@@ -47,8 +51,7 @@ async fn shuffle(ctx: &Context, msg: &Message) -> CommandResult {
         let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
 
         if queue.is_empty() {
-            msg.reply_mention(ctx, "The queue is currently empty")
-                .await?;
+            invoker.reply(ctx, "The queue is currently empty").await?;
             return Ok(());
         }
 
@@ -58,7 +61,7 @@ async fn shuffle(ctx: &Context, msg: &Message) -> CommandResult {
             queue.push_front(playing_track);
         });
 
-        msg.channel_id.say(ctx, "Shuffled queue").await?;
+        invoker.say(ctx, "Shuffled queue").await?;
     }
 
     Ok(())
@@ -4,14 +4,18 @@ use serenity::{
     prelude::*,
 };
 
-use crate::{checks::*, queue::get_queue_from_ctx_and_guild_id};
+use crate::{checks::*, invoker::Invoker, queue::get_queue_from_ctx_and_guild_id};
 
 #[command]
 #[checks(dj_only)]
 #[description = "Skips the currently playing track"]
 #[bucket = "global"]
 async fn skip(ctx: &Context, msg: &Message) -> CommandResult {
-    let guild_id = msg.guild_id.unwrap();
+    skip_track(ctx, msg.into()).await
+}
+
+pub async fn skip_track(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild_id = invoker.guild_id();
 
     let manager = songbird::get(ctx).await.unwrap().clone();
 
@@ -23,20 +27,18 @@ async fn skip(ctx: &Context, msg: &Message) -> CommandResult {
         if current.is_some() {
             queue.skip()?;
         } else {
-            msg.reply_ping(ctx, "No song currently playing").await?;
+            invoker.reply(ctx, "No song currently playing").await?;
             return Ok(());
         }
 
-        msg.channel_id
+        invoker
             .say(
                 ctx,
                 format!("Song skipped: {} songs left in queue.", queue.len() - 1),
             )
             .await?;
     } else {
-        msg.channel_id
-            .say(ctx, "Not in a voice channel to skip")
-            .await?;
+        invoker.say(ctx, "Not in a voice channel to skip").await?;
     }
 
     Ok(())
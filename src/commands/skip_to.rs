@@ -0,0 +1,48 @@
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::prelude::*,
+    prelude::*,
+};
+
+use crate::{checks::*, invoker::Invoker, queue::get_queue_from_ctx_and_guild_id};
+
+#[command("skipto")]
+#[checks(dj_only)]
+#[description = "Skips to a song in the queue, removing every song before it"]
+#[usage = "<index of song to skip to>"]
+#[bucket = "global"]
+async fn skip_to(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let index = args.single::<usize>()?;
+
+    skip_to_track(ctx, msg.into(), index).await
+}
+
+pub async fn skip_to_track(ctx: &Context, invoker: Invoker<'_>, index: usize) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let manager = songbird::get(ctx).await.unwrap().clone();
+
+    if manager.get(guild_id).is_some() {
+        let mut queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
+
+        if index == 0 || index >= queue.len() {
+            invoker
+                .reply(ctx, format!("There is no song at index: {index}"))
+                .await?;
+            return Ok(());
+        }
+
+        let title = queue.modify_queue(|queue| {
+            queue.drain(1..index);
+            queue[1].display_title().to_string()
+        });
+
+        queue.skip()?;
+
+        invoker.say(ctx, format!("Skipped to `{title}`")).await?;
+    } else {
+        invoker.reply(ctx, "Nothing playing").await?;
+    }
+
+    Ok(())
+}
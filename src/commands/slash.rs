@@ -0,0 +1,48 @@
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::prelude::*,
+    prelude::*,
+};
+
+use crate::slash_commands::{clear_commands, register_global_commands, register_guild_commands};
+
+#[command]
+#[owners_only]
+#[help_available(false)]
+#[usage = "<guild | global | clear>"]
+async fn slash_commands(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let guild_id = msg.guild_id.unwrap();
+
+    match args.single_quoted::<String>()?.to_lowercase().as_ref() {
+        "guild" => {
+            let commands = register_guild_commands(ctx, guild_id).await?;
+            msg.reply_ping(
+                ctx,
+                format!("Registered {} slash commands in this guild", commands.len()),
+            )
+            .await?;
+        }
+        "global" => {
+            let commands = register_global_commands(ctx).await?;
+            msg.reply_ping(
+                ctx,
+                format!("Registered {} slash commands globally", commands.len()),
+            )
+            .await?;
+        }
+        "clear" => {
+            clear_commands(ctx, guild_id).await?;
+            msg.reply_ping(
+                ctx,
+                "Removed the slash commands from this guild and globally",
+            )
+            .await?;
+        }
+        _ => {
+            msg.reply_ping(ctx, "Options are: `guild`, `global`, and `clear`")
+                .await?;
+        }
+    }
+
+    Ok(())
+}
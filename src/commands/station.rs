@@ -0,0 +1,216 @@
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::prelude::*,
+    prelude::*,
+    utils::Color,
+};
+
+use super::play::{add_live_stream, get_or_join_handler};
+use crate::{
+    checks::*,
+    data::PoolContainer,
+    db::{
+        add_radio_station, get_radio_station, get_radio_stations, remove_radio_station,
+        RadioStation,
+    },
+    invoker::Invoker,
+    radio::probe_radio_stream,
+};
+
+const MAX_NAME_LENGTH: usize = 100;
+
+#[command]
+#[aliases("stations")]
+#[checks(not_blacklisted)]
+#[description = "Lists the radio stations saved on this server, use ~station play to listen to one"]
+#[sub_commands(station_play, station_add, station_remove)]
+#[bucket = "global"]
+async fn station(ctx: &Context, msg: &Message) -> CommandResult {
+    show_stations(ctx, msg.into()).await
+}
+
+#[command("play")]
+#[checks(dj_only)]
+#[description = "Adds a saved radio station to the queue"]
+#[usage = "<name of station>"]
+#[bucket = "global"]
+async fn station_play(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let name = args.remains().unwrap_or_default().to_string();
+
+    play_station(ctx, msg.into(), &name).await
+}
+
+#[command("add")]
+#[checks(admin_only)]
+#[description = "Saves a radio station for everyone on this server"]
+#[usage = "<name of station> <link to the stream>"]
+#[bucket = "global"]
+async fn station_add(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let (name, url) = args
+        .remains()
+        .and_then(|rest| rest.rsplit_once(' '))
+        .unwrap_or_default();
+
+    add_station(ctx, msg.into(), name.trim(), url.trim()).await
+}
+
+#[command("remove")]
+#[checks(admin_only)]
+#[description = "Removes a saved radio station"]
+#[usage = "<name of station>"]
+#[bucket = "global"]
+async fn station_remove(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let name = args.remains().unwrap_or_default().to_string();
+
+    remove_station(ctx, msg.into(), &name).await
+}
+
+pub async fn show_stations(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let stations = {
+        let data = ctx.data.read().await;
+        let pool = data.get::<PoolContainer>().unwrap();
+
+        get_radio_stations(pool, invoker.guild_id().into()).await?
+    };
+
+    if stations.is_empty() {
+        invoker
+            .say(
+                ctx,
+                "There are no radio stations saved, admins can add them with ~station add",
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let description = stations
+        .iter()
+        .map(|station| format!("**{}** <{}>", station.name, station.url))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    invoker
+        .send_embed(ctx, |e| {
+            e.title("Radio stations")
+                .description(description)
+                .footer(|f| f.text(format!("{} stations", stations.len())))
+                .color(Color::DARK_GREEN)
+        })
+        .await?;
+
+    Ok(())
+}
+
+pub async fn play_station(ctx: &Context, invoker: Invoker<'_>, name: &str) -> CommandResult {
+    let station = {
+        let data = ctx.data.read().await;
+        let pool = data.get::<PoolContainer>().unwrap();
+
+        get_radio_station(pool, invoker.guild_id().into(), name).await?
+    };
+
+    let station = match station {
+        Some(station) => station,
+        None => {
+            invoker
+                .reply(ctx, format!("There is no radio station called `{name}`"))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let handler_lock = match get_or_join_handler(ctx, invoker).await? {
+        Some(handler_lock) => handler_lock,
+        None => return Ok(()),
+    };
+
+    add_live_stream(ctx, invoker, handler_lock, station.url, station.name).await
+}
+
+pub async fn add_station(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    name: &str,
+    url: &str,
+) -> CommandResult {
+    if name.is_empty() || !url.starts_with("http") {
+        invoker
+            .reply(
+                ctx,
+                "Please include a name for the station and a link to its stream",
+            )
+            .await?;
+        return Ok(());
+    }
+
+    if name.chars().count() > MAX_NAME_LENGTH {
+        invoker
+            .reply(
+                ctx,
+                format!("Station names can be at most {MAX_NAME_LENGTH} characters long"),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    if probe_radio_stream(url).await.is_none() {
+        invoker
+            .reply(
+                ctx,
+                "That link is not a radio stream, it has to be an icecast, shoutcast or HLS stream",
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let added = {
+        let data = ctx.data.read().await;
+        let pool = data.get::<PoolContainer>().unwrap();
+
+        let station = RadioStation {
+            name: name.to_string(),
+            url: url.to_string(),
+        };
+
+        add_radio_station(pool, invoker.guild_id().into(), &station).await?
+    };
+
+    if added {
+        invoker
+            .say(ctx, format!("Saved the radio station `{name}`"))
+            .await?;
+    } else {
+        invoker
+            .reply(
+                ctx,
+                format!("There already is a radio station called `{name}`"),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn remove_station(ctx: &Context, invoker: Invoker<'_>, name: &str) -> CommandResult {
+    let removed = {
+        let data = ctx.data.read().await;
+        let pool = data.get::<PoolContainer>().unwrap();
+
+        remove_radio_station(pool, invoker.guild_id().into(), name).await?
+    };
+
+    match removed {
+        Some(station) => {
+            invoker
+                .say(ctx, format!("Removed the radio station `{}`", station.name))
+                .await?;
+        }
+        None => {
+            invoker
+                .reply(ctx, format!("There is no radio station called `{name}`"))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
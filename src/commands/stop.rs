@@ -4,7 +4,10 @@ use serenity::{
     prelude::*,
 };
 
-use crate::{checks::*, queue::QueueMap};
+use crate::{
+    checks::*, data::PoolContainer, db::delete_queue_snapshot, fades::fade_out,
+    history::save_history, invoker::Invoker, now_playing_panel::close_panel, queue::QueueMap,
+};
 
 #[command]
 #[checks(dj_only)]
@@ -12,7 +15,11 @@ use crate::{checks::*, queue::QueueMap};
 #[aliases("leave", "die")]
 #[bucket = "global"]
 async fn stop(ctx: &Context, msg: &Message) -> CommandResult {
-    let guild_id = msg.guild_id.unwrap();
+    stop_playing(ctx, msg.into()).await
+}
+
+pub async fn stop_playing(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild_id = invoker.guild_id();
 
     let manager = songbird::get(ctx).await.unwrap().clone();
 
@@ -21,7 +28,19 @@ async fn stop(ctx: &Context, msg: &Message) -> CommandResult {
             let data = ctx.data.read().await;
             let queue_container = data.get::<QueueMap>().unwrap().clone();
             let queue = queue_container.remove(&guild_id).unwrap();
+
+            let current = { queue.1.current().lock().clone() };
+
+            if let Some(handle) = current {
+                let _ = fade_out(&handle).await;
+            }
+
             queue.1.stop();
+            close_panel(&ctx.http, &queue.1).await?;
+
+            let pool = data.get::<PoolContainer>().unwrap();
+            save_history(pool, guild_id, &queue.1).await?;
+            delete_queue_snapshot(pool, guild_id.into()).await?;
 
             let mut handler = handler_lock.lock().await;
             handler.remove_all_global_events();
@@ -29,9 +48,9 @@ async fn stop(ctx: &Context, msg: &Message) -> CommandResult {
 
         manager.remove(guild_id).await?;
 
-        msg.channel_id.say(ctx, "Cleared queue").await?;
+        invoker.say(ctx, "Cleared queue").await?;
     } else {
-        msg.reply_ping(ctx, "Not in a voice channel").await?;
+        invoker.reply(ctx, "Not in a voice channel").await?;
     }
 
     Ok(())
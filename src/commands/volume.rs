@@ -6,6 +6,7 @@ use serenity::{
 
 use crate::{
     checks::*,
+    invoker::Invoker,
     queue::{get_queue_from_ctx_and_guild_id, QueueMap},
 };
 
@@ -16,11 +17,37 @@ use crate::{
 #[usage = "to see the current volume | volume <number 0-100> to set the volume"]
 #[bucket = "global"]
 async fn volume(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let guild_id = msg.guild_id.unwrap();
+    set_volume(ctx, msg.into(), args.single_quoted::<i32>().ok()).await
+}
+
+pub async fn change_volume(ctx: &Context, invoker: Invoker<'_>, step: i32) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
+
+    let current = { queue.current().lock().clone() };
+
+    if let Some(handle) = current {
+        let current_volume = (handle.get_info().await?.volume * 100f32).round() as i32;
+
+        set_volume(ctx, invoker, Some((current_volume + step).clamp(0, 100))).await
+    } else {
+        invoker.reply(ctx, "Nothing playing").await?;
+
+        Ok(())
+    }
+}
+
+pub async fn set_volume(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    new_volume: Option<i32>,
+) -> CommandResult {
+    let guild_id = invoker.guild_id();
 
-    let new_volume = match args.single_quoted::<i32>() {
-        Ok(vol) => vol,
-        Err(_) => {
+    let new_volume = match new_volume {
+        Some(vol) => vol,
+        None => {
             let manager = songbird::get(ctx).await.unwrap().clone();
 
             if manager.get(guild_id).is_some() {
@@ -34,14 +61,14 @@ async fn volume(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
                     let mut current_volume = handle.get_info().await?.volume * 100f32;
                     current_volume = current_volume.round();
 
-                    msg.channel_id
+                    invoker
                         .say(ctx, format!("The current volume is {current_volume}"))
                         .await?;
                 } else {
-                    msg.reply_ping(ctx, "Nothing playing").await?;
+                    invoker.reply(ctx, "Nothing playing").await?;
                 }
             } else {
-                msg.reply_ping(ctx, "Not in a voice channel").await?;
+                invoker.reply(ctx, "Not in a voice channel").await?;
             }
 
             return Ok(());
@@ -49,7 +76,8 @@ async fn volume(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     };
 
     if !(0..=100).contains(&new_volume) {
-        msg.reply_ping(ctx, "Please select a value from 0 to 100")
+        invoker
+            .reply(ctx, "Please select a value from 0 to 100")
             .await?;
         return Ok(());
     }
@@ -66,10 +94,10 @@ async fn volume(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
         if let Some(handle) = current {
             handle.set_volume(new_volume)?;
         } else {
-            msg.reply_ping(ctx, "Nothing playing").await?;
+            invoker.reply(ctx, "Nothing playing").await?;
         }
     } else {
-        msg.reply_ping(ctx, "Not in a voice channel").await?;
+        invoker.reply(ctx, "Not in a voice channel").await?;
     }
 
     Ok(())
@@ -0,0 +1,169 @@
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::prelude::*,
+    prelude::*,
+};
+
+use crate::{
+    checks::*,
+    data::PoolContainer,
+    db::{get_skip_threshold, set_skip_threshold},
+    invoker::Invoker,
+    queue::get_queue_from_ctx_and_guild_id,
+    skip_threshold::SkipThreshold,
+};
+
+#[command]
+#[aliases("vs")]
+#[checks(not_blacklisted)]
+#[description = "Votes to skip the currently playing track, the person who requested it can skip it straight away"]
+#[bucket = "global"]
+async fn voteskip(ctx: &Context, msg: &Message) -> CommandResult {
+    vote_skip(ctx, msg.into()).await
+}
+
+pub async fn vote_skip(ctx: &Context, invoker: Invoker<'_>) -> CommandResult {
+    let guild_id = invoker.guild_id();
+    let author_id = invoker.author_id();
+
+    let manager = songbird::get(ctx).await.unwrap().clone();
+
+    if manager.get(guild_id).is_none() {
+        invoker.say(ctx, "Not in a voice channel to skip").await?;
+        return Ok(());
+    }
+
+    let mut queue = get_queue_from_ctx_and_guild_id(ctx, guild_id).await;
+
+    let current = { queue.current().lock().clone() };
+
+    let current_track = match (current, queue.current_queue().first()) {
+        (Some(_), Some(track)) => track.clone(),
+        _ => {
+            invoker.reply(ctx, "No song currently playing").await?;
+            return Ok(());
+        }
+    };
+
+    let guild = invoker.guild(ctx).await;
+    let bot_id = ctx.cache.current_user_id().await;
+
+    let bot_channel = guild
+        .voice_states
+        .get(&bot_id)
+        .and_then(|state| state.channel_id);
+
+    let author_channel = guild
+        .voice_states
+        .get(&author_id)
+        .and_then(|state| state.channel_id);
+
+    if bot_channel.is_none() || author_channel != bot_channel {
+        invoker
+            .reply(ctx, "You have to be listening to vote to skip")
+            .await?;
+        return Ok(());
+    }
+
+    if current_track.requester == Some(author_id) {
+        queue.skip()?;
+        invoker.say(ctx, "Skipped your song").await?;
+        return Ok(());
+    }
+
+    let listeners = guild
+        .voice_states
+        .values()
+        .filter(|state| state.channel_id == bot_channel)
+        .filter(|state| {
+            guild
+                .members
+                .get(&state.user_id)
+                .map_or(true, |member| !member.user.bot)
+        })
+        .count();
+
+    let threshold = {
+        let data = ctx.data.read().await;
+        let pool = data.get::<PoolContainer>().unwrap();
+
+        get_skip_threshold(pool, guild_id.into()).await?
+    };
+
+    let required = threshold.required_votes(listeners);
+
+    let votes = match queue.add_skip_vote(author_id) {
+        Some(votes) => votes,
+        None => {
+            invoker
+                .reply(ctx, "You already voted to skip this song")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    if votes >= required {
+        queue.skip()?;
+        invoker
+            .say(
+                ctx,
+                format!("Vote passed ({votes}/{required}), song skipped"),
+            )
+            .await?;
+    } else {
+        invoker
+            .say(ctx, format!("Voted to skip ({votes}/{required})"))
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[command("skipthreshold")]
+#[checks(admin_only)]
+#[description = "Shows or changes how many listeners have to vote to skip a song"]
+#[usage = "<number of votes | percentage of listeners, like 50%>"]
+#[bucket = "global"]
+async fn skip_threshold(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let threshold = args.single_quoted::<String>().ok();
+
+    change_skip_threshold(ctx, msg.into(), threshold).await
+}
+
+pub async fn change_skip_threshold(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    threshold: Option<String>,
+) -> CommandResult {
+    let guild_id = invoker.guild_id();
+
+    let data = ctx.data.read().await;
+    let pool = data.get::<PoolContainer>().unwrap();
+
+    let threshold = match threshold {
+        Some(threshold) => threshold,
+        None => {
+            let threshold = get_skip_threshold(pool, guild_id.into()).await?;
+            invoker
+                .say(ctx, format!("Skipping a song takes {threshold}"))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let threshold = match threshold.parse::<SkipThreshold>() {
+        Ok(threshold) => threshold,
+        Err(e) => {
+            invoker.reply(ctx, e).await?;
+            return Ok(());
+        }
+    };
+
+    set_skip_threshold(pool, guild_id.into(), threshold).await?;
+
+    invoker
+        .say(ctx, format!("Skipping a song now takes {threshold}"))
+        .await?;
+
+    Ok(())
+}
@@ -1,10 +1,16 @@
-use std::convert::{TryFrom, TryInto};
+use std::{
+    convert::{TryFrom, TryInto},
+    time::Duration,
+};
 
 use serenity::model::id::GuildId;
-use sqlx::postgres::{PgPool, PgQueryResult};
+use sqlx::{
+    postgres::{PgPool, PgQueryResult},
+    Postgres, Transaction,
+};
 use tracing::debug;
 
-use crate::data::PrefixCacheInternal;
+use crate::{data::PrefixCacheInternal, queue_limits::QueueLimits, skip_threshold::SkipThreshold};
 
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
 pub enum UserPerm {
@@ -272,3 +278,942 @@ pub async fn delete_guild_prefix(
 
     Ok(())
 }
+
+#[derive(Debug)]
+pub struct QueueSnapshot {
+    pub guild_id: i64,
+    pub voice_channel_id: i64,
+    pub text_channel_id: i64,
+    pub position_ms: i64,
+    pub volume: f32,
+    pub repeat_mode: i16,
+    pub repeat_times: i32,
+}
+
+#[derive(Debug)]
+pub struct SnapshotTrack {
+    pub name: String,
+    pub requester_id: Option<i64>,
+    pub title: Option<String>,
+    pub live: bool,
+    pub radio: bool,
+    pub stream_title: Option<String>,
+}
+
+/// Saves the queue's state, its tracks are only rewritten when they are given.
+pub async fn save_queue_snapshot(
+    pool: &PgPool,
+    snapshot: &QueueSnapshot,
+    tracks: Option<&[SnapshotTrack]>,
+) -> anyhow::Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO guilds (guild_id)
+        VALUES ($1)
+        ON CONFLICT DO NOTHING"#,
+        snapshot.guild_id
+    )
+    .execute(&mut tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO queues (guild_id, voice_channel_id, text_channel_id, position_ms, volume, repeat_mode, repeat_times)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (guild_id)
+        DO UPDATE SET
+            voice_channel_id = EXCLUDED.voice_channel_id,
+            text_channel_id = EXCLUDED.text_channel_id,
+            position_ms = EXCLUDED.position_ms,
+            volume = EXCLUDED.volume,
+            repeat_mode = EXCLUDED.repeat_mode,
+            repeat_times = EXCLUDED.repeat_times"#,
+        snapshot.guild_id,
+        snapshot.voice_channel_id,
+        snapshot.text_channel_id,
+        snapshot.position_ms,
+        snapshot.volume,
+        snapshot.repeat_mode,
+        snapshot.repeat_times
+    )
+    .execute(&mut tx)
+    .await?;
+
+    if let Some(tracks) = tracks {
+        sqlx::query!(
+            r#"
+            DELETE FROM queued_tracks
+            WHERE guild_id = $1"#,
+            snapshot.guild_id
+        )
+        .execute(&mut tx)
+        .await?;
+
+        // Arrays can not hold NULL here, so missing values are sent as 0 or an empty string
+        let names: Vec<String> = tracks.iter().map(|track| track.name.clone()).collect();
+        let requester_ids: Vec<i64> = tracks
+            .iter()
+            .map(|track| track.requester_id.unwrap_or_default())
+            .collect();
+        let titles: Vec<String> = tracks
+            .iter()
+            .map(|track| track.title.clone().unwrap_or_default())
+            .collect();
+        let lives: Vec<bool> = tracks.iter().map(|track| track.live).collect();
+        let radios: Vec<bool> = tracks.iter().map(|track| track.radio).collect();
+        let stream_titles: Vec<String> = tracks
+            .iter()
+            .map(|track| track.stream_title.clone().unwrap_or_default())
+            .collect();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO queued_tracks (guild_id, position, name, requester_id, title, live, radio, stream_title)
+            SELECT $1, track.position, track.name, NULLIF(track.requester_id, 0), NULLIF(track.title, ''),
+                track.live, track.radio, NULLIF(track.stream_title, '')
+            FROM UNNEST($2::TEXT[], $3::INT8[], $4::TEXT[], $5::BOOL[], $6::BOOL[], $7::TEXT[])
+                WITH ORDINALITY AS track(name, requester_id, title, live, radio, stream_title, position)"#,
+            snapshot.guild_id,
+            &names,
+            &requester_ids,
+            &titles,
+            &lives,
+            &radios,
+            &stream_titles
+        )
+        .execute(&mut tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn get_queue_snapshots(
+    pool: &PgPool,
+    guild_ids: &[i64],
+) -> anyhow::Result<Vec<QueueSnapshot>> {
+    let rec: Vec<QueueSnapshot> = sqlx::query_as!(
+        QueueSnapshot,
+        r#"
+        SELECT guild_id, voice_channel_id, text_channel_id, position_ms, volume, repeat_mode, repeat_times
+        FROM queues
+        WHERE guild_id = ANY($1)"#,
+        guild_ids
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rec)
+}
+
+pub async fn get_queued_tracks(pool: &PgPool, guild_id: i64) -> anyhow::Result<Vec<SnapshotTrack>> {
+    let rec: Vec<SnapshotTrack> = sqlx::query_as!(
+        SnapshotTrack,
+        r#"
+        SELECT name, requester_id, title, live, radio, stream_title
+        FROM queued_tracks
+        WHERE guild_id = $1
+        ORDER BY position"#,
+        guild_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rec)
+}
+
+pub async fn delete_queue_snapshot(pool: &PgPool, guild_id: i64) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM queues
+        WHERE guild_id = $1"#,
+        guild_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_skip_threshold(pool: &PgPool, guild_id: i64) -> anyhow::Result<SkipThreshold> {
+    let rec = match sqlx::query!(
+        r#"
+        SELECT threshold, is_percentage
+        FROM skip_thresholds
+        WHERE guild_id = $1"#,
+        guild_id
+    )
+    .fetch_optional(pool)
+    .await?
+    {
+        Some(row) => row,
+        None => return Ok(SkipThreshold::default()),
+    };
+
+    let threshold = if rec.is_percentage {
+        SkipThreshold::Percentage(rec.threshold.try_into()?)
+    } else {
+        SkipThreshold::Count(rec.threshold.try_into()?)
+    };
+
+    Ok(threshold)
+}
+
+pub async fn set_skip_threshold(
+    pool: &PgPool,
+    guild_id: i64,
+    threshold: SkipThreshold,
+) -> anyhow::Result<()> {
+    let (threshold, is_percentage) = match threshold {
+        SkipThreshold::Percentage(percentage) => (i32::from(percentage), true),
+        SkipThreshold::Count(count) => (count.try_into()?, false),
+    };
+
+    sqlx::query!(
+        r#"
+        INSERT INTO skip_thresholds (guild_id, threshold, is_percentage)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (guild_id)
+        DO UPDATE SET
+            threshold = EXCLUDED.threshold,
+            is_percentage = EXCLUDED.is_percentage"#,
+        guild_id,
+        threshold,
+        is_percentage
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_fair_queue(pool: &PgPool, guild_id: i64) -> anyhow::Result<bool> {
+    let rec = sqlx::query!(
+        r#"
+        SELECT fair_queue
+        FROM queue_settings
+        WHERE guild_id = $1"#,
+        guild_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(rec.map_or(false, |row| row.fair_queue))
+}
+
+pub async fn set_fair_queue(pool: &PgPool, guild_id: i64, fair_queue: bool) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO queue_settings (guild_id, fair_queue)
+        VALUES ($1, $2)
+        ON CONFLICT (guild_id)
+        DO UPDATE SET
+            fair_queue = EXCLUDED.fair_queue"#,
+        guild_id,
+        fair_queue
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_autoplay(pool: &PgPool, guild_id: i64) -> anyhow::Result<bool> {
+    let rec = sqlx::query!(
+        r#"
+        SELECT autoplay
+        FROM queue_settings
+        WHERE guild_id = $1"#,
+        guild_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(rec.map_or(false, |row| row.autoplay))
+}
+
+pub async fn set_autoplay(pool: &PgPool, guild_id: i64, autoplay: bool) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO queue_settings (guild_id, autoplay)
+        VALUES ($1, $2)
+        ON CONFLICT (guild_id)
+        DO UPDATE SET
+            autoplay = EXCLUDED.autoplay"#,
+        guild_id,
+        autoplay
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_normalise(pool: &PgPool, guild_id: i64) -> anyhow::Result<bool> {
+    let rec = sqlx::query!(
+        r#"
+        SELECT normalise
+        FROM queue_settings
+        WHERE guild_id = $1"#,
+        guild_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(rec.map_or(false, |row| row.normalise))
+}
+
+pub async fn set_normalise(pool: &PgPool, guild_id: i64, normalise: bool) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO queue_settings (guild_id, normalise)
+        VALUES ($1, $2)
+        ON CONFLICT (guild_id)
+        DO UPDATE SET
+            normalise = EXCLUDED.normalise"#,
+        guild_id,
+        normalise
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_crossfade(pool: &PgPool, guild_id: i64) -> anyhow::Result<Duration> {
+    let rec = sqlx::query!(
+        r#"
+        SELECT crossfade_secs
+        FROM queue_settings
+        WHERE guild_id = $1"#,
+        guild_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let secs = rec.map_or(0, |row| row.crossfade_secs);
+
+    Ok(Duration::from_secs(secs.try_into().unwrap_or_default()))
+}
+
+pub async fn set_crossfade(
+    pool: &PgPool,
+    guild_id: i64,
+    crossfade: Duration,
+) -> anyhow::Result<()> {
+    let secs: i32 = crossfade.as_secs().try_into()?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO queue_settings (guild_id, crossfade_secs)
+        VALUES ($1, $2)
+        ON CONFLICT (guild_id)
+        DO UPDATE SET
+            crossfade_secs = EXCLUDED.crossfade_secs"#,
+        guild_id,
+        secs
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_queue_limits(pool: &PgPool, guild_id: i64) -> anyhow::Result<QueueLimits> {
+    let rec = match sqlx::query!(
+        r#"
+        SELECT max_user_tracks, max_queue_length, max_track_length_secs, max_playlist_size, exempt_djs
+        FROM queue_settings
+        WHERE guild_id = $1"#,
+        guild_id
+    )
+    .fetch_optional(pool)
+    .await?
+    {
+        Some(row) => row,
+        None => return Ok(QueueLimits::default()),
+    };
+
+    Ok(QueueLimits {
+        max_user_tracks: rec.max_user_tracks.map(TryInto::try_into).transpose()?,
+        max_queue_length: rec.max_queue_length.map(TryInto::try_into).transpose()?,
+        max_track_length: rec
+            .max_track_length_secs
+            .map(|secs| secs.try_into().map(Duration::from_secs))
+            .transpose()?,
+        max_playlist_size: rec.max_playlist_size.map(TryInto::try_into).transpose()?,
+        exempt_djs: rec.exempt_djs,
+    })
+}
+
+pub async fn set_queue_limits(
+    pool: &PgPool,
+    guild_id: i64,
+    limits: QueueLimits,
+) -> anyhow::Result<()> {
+    let max_user_tracks: Option<i32> = limits.max_user_tracks.map(TryInto::try_into).transpose()?;
+    let max_queue_length: Option<i32> =
+        limits.max_queue_length.map(TryInto::try_into).transpose()?;
+    let max_track_length_secs: Option<i32> = limits
+        .max_track_length
+        .map(|length| length.as_secs().try_into())
+        .transpose()?;
+    let max_playlist_size: Option<i32> = limits
+        .max_playlist_size
+        .map(TryInto::try_into)
+        .transpose()?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO queue_settings (guild_id, max_user_tracks, max_queue_length, max_track_length_secs, max_playlist_size, exempt_djs)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (guild_id)
+        DO UPDATE SET
+            max_user_tracks = EXCLUDED.max_user_tracks,
+            max_queue_length = EXCLUDED.max_queue_length,
+            max_track_length_secs = EXCLUDED.max_track_length_secs,
+            max_playlist_size = EXCLUDED.max_playlist_size,
+            exempt_djs = EXCLUDED.exempt_djs"#,
+        guild_id,
+        max_user_tracks,
+        max_queue_length,
+        max_track_length_secs,
+        max_playlist_size,
+        limits.exempt_djs
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+#[derive(Debug)]
+pub struct HistoryEntry {
+    pub name: String,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub duration_ms: Option<i64>,
+    pub requester_id: Option<i64>,
+}
+
+/// Adds the entries, the oldest first, and drops all but the newest `limit` entries of the guild.
+pub async fn add_history_entries(
+    pool: &PgPool,
+    guild_id: i64,
+    entries: &[HistoryEntry],
+    limit: i64,
+) -> anyhow::Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO guilds (guild_id)
+        VALUES ($1)
+        ON CONFLICT DO NOTHING"#,
+        guild_id
+    )
+    .execute(&mut tx)
+    .await?;
+
+    for entry in entries {
+        sqlx::query!(
+            r#"
+            INSERT INTO history (guild_id, name, title, url, duration_ms, requester_id)
+            VALUES ($1, $2, $3, $4, $5, $6)"#,
+            guild_id,
+            entry.name,
+            entry.title,
+            entry.url,
+            entry.duration_ms,
+            entry.requester_id
+        )
+        .execute(&mut tx)
+        .await?;
+    }
+
+    sqlx::query!(
+        r#"
+        DELETE FROM history
+        WHERE guild_id = $1 AND id NOT IN (
+            SELECT id
+            FROM history
+            WHERE guild_id = $1
+            ORDER BY id DESC
+            LIMIT $2
+        )"#,
+        guild_id,
+        limit
+    )
+    .execute(&mut tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Returns the newest entries first.
+pub async fn get_history(
+    pool: &PgPool,
+    guild_id: i64,
+    limit: i64,
+) -> anyhow::Result<Vec<HistoryEntry>> {
+    let rec: Vec<HistoryEntry> = sqlx::query_as!(
+        HistoryEntry,
+        r#"
+        SELECT name, title, url, duration_ms, requester_id
+        FROM history
+        WHERE guild_id = $1
+        ORDER BY id DESC
+        LIMIT $2"#,
+        guild_id,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rec)
+}
+
+#[derive(Debug)]
+pub struct Playlist {
+    pub playlist_id: i64,
+    pub guild_id: i64,
+    pub owner_id: i64,
+    pub name: String,
+    pub shared: bool,
+}
+
+#[derive(Debug)]
+pub struct PlaylistTrack {
+    pub name: String,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub duration_ms: Option<i64>,
+}
+
+/// Returns `None` if the owner already has a playlist with that name.
+pub async fn create_playlist(
+    pool: &PgPool,
+    guild_id: i64,
+    owner_id: i64,
+    name: &str,
+    shared: bool,
+) -> anyhow::Result<Option<Playlist>> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO guilds (guild_id)
+        VALUES ($1)
+        ON CONFLICT DO NOTHING"#,
+        guild_id
+    )
+    .execute(&mut tx)
+    .await?;
+
+    let rec = sqlx::query_as!(
+        Playlist,
+        r#"
+        INSERT INTO playlists (guild_id, owner_id, name, shared)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT DO NOTHING
+        RETURNING playlist_id, guild_id, owner_id, name, shared"#,
+        guild_id,
+        owner_id,
+        name,
+        shared
+    )
+    .fetch_optional(&mut tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(rec)
+}
+
+/// Finds a playlist the user can see, their own playlists take priority over shared ones.
+pub async fn find_playlist(
+    pool: &PgPool,
+    guild_id: i64,
+    user_id: i64,
+    name: &str,
+) -> anyhow::Result<Option<Playlist>> {
+    let rec = sqlx::query_as!(
+        Playlist,
+        r#"
+        SELECT playlist_id, guild_id, owner_id, name, shared
+        FROM playlists
+        WHERE guild_id = $1 AND name = $3 AND (owner_id = $2 OR shared)
+        ORDER BY owner_id = $2 DESC, playlist_id
+        LIMIT 1"#,
+        guild_id,
+        user_id,
+        name
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(rec)
+}
+
+/// Lists the user's own playlists and the ones shared in the guild.
+pub async fn get_playlists(
+    pool: &PgPool,
+    guild_id: i64,
+    user_id: i64,
+) -> anyhow::Result<Vec<Playlist>> {
+    let rec = sqlx::query_as!(
+        Playlist,
+        r#"
+        SELECT playlist_id, guild_id, owner_id, name, shared
+        FROM playlists
+        WHERE guild_id = $1 AND (owner_id = $2 OR shared)
+        ORDER BY name"#,
+        guild_id,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rec)
+}
+
+pub async fn set_playlist_shared(
+    pool: &PgPool,
+    playlist_id: i64,
+    shared: bool,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE playlists
+        SET shared = $2
+        WHERE playlist_id = $1"#,
+        playlist_id,
+        shared
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_playlist(pool: &PgPool, playlist_id: i64) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM playlists
+        WHERE playlist_id = $1"#,
+        playlist_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_playlist_tracks(
+    pool: &PgPool,
+    playlist_id: i64,
+) -> anyhow::Result<Vec<PlaylistTrack>> {
+    let rec = sqlx::query_as!(
+        PlaylistTrack,
+        r#"
+        SELECT name, title, url, duration_ms
+        FROM playlist_tracks
+        WHERE playlist_id = $1
+        ORDER BY position"#,
+        playlist_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rec)
+}
+
+async fn insert_playlist_tracks(
+    tx: &mut Transaction<'_, Postgres>,
+    playlist_id: i64,
+    tracks: &[PlaylistTrack],
+) -> anyhow::Result<()> {
+    // Locking the playlist keeps concurrent appends from picking the same positions
+    sqlx::query!(
+        r#"
+        SELECT playlist_id
+        FROM playlists
+        WHERE playlist_id = $1
+        FOR UPDATE"#,
+        playlist_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    // Arrays can not hold NULL here, so missing values are sent as -1 or an empty string
+    let names: Vec<String> = tracks.iter().map(|track| track.name.clone()).collect();
+    let titles: Vec<String> = tracks
+        .iter()
+        .map(|track| track.title.clone().unwrap_or_default())
+        .collect();
+    let urls: Vec<String> = tracks
+        .iter()
+        .map(|track| track.url.clone().unwrap_or_default())
+        .collect();
+    let durations_ms: Vec<i64> = tracks
+        .iter()
+        .map(|track| track.duration_ms.unwrap_or(-1))
+        .collect();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO playlist_tracks (playlist_id, position, name, title, url, duration_ms)
+        SELECT $1, last.position + track.position, track.name, NULLIF(track.title, ''),
+            NULLIF(track.url, ''), NULLIF(track.duration_ms, -1)
+        FROM (
+            SELECT COALESCE(MAX(position), 0) AS position
+            FROM playlist_tracks
+            WHERE playlist_id = $1
+        ) AS last,
+        UNNEST($2::TEXT[], $3::TEXT[], $4::TEXT[], $5::INT8[])
+            WITH ORDINALITY AS track(name, title, url, duration_ms, position)"#,
+        playlist_id,
+        &names,
+        &titles,
+        &urls,
+        &durations_ms
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Appends the tracks to the end of the playlist.
+pub async fn add_playlist_tracks(
+    pool: &PgPool,
+    playlist_id: i64,
+    tracks: &[PlaylistTrack],
+) -> anyhow::Result<()> {
+    let mut tx = pool.begin().await?;
+
+    insert_playlist_tracks(&mut tx, playlist_id, tracks).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Replaces every track of the playlist.
+pub async fn set_playlist_tracks(
+    pool: &PgPool,
+    playlist_id: i64,
+    tracks: &[PlaylistTrack],
+) -> anyhow::Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM playlist_tracks
+        WHERE playlist_id = $1"#,
+        playlist_id
+    )
+    .execute(&mut tx)
+    .await?;
+
+    insert_playlist_tracks(&mut tx, playlist_id, tracks).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Removes the track at the index, starting at 0, in the order the tracks are played.
+pub async fn remove_playlist_track(
+    pool: &PgPool,
+    playlist_id: i64,
+    index: i64,
+) -> anyhow::Result<Option<PlaylistTrack>> {
+    let rec = sqlx::query_as!(
+        PlaylistTrack,
+        r#"
+        DELETE FROM playlist_tracks
+        WHERE playlist_id = $1 AND position = (
+            SELECT position
+            FROM playlist_tracks
+            WHERE playlist_id = $1
+            ORDER BY position
+            OFFSET $2
+            LIMIT 1
+        )
+        RETURNING name, title, url, duration_ms"#,
+        playlist_id,
+        index
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(rec)
+}
+
+#[derive(Debug)]
+pub struct Favourite {
+    pub name: String,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub duration_ms: Option<i64>,
+}
+
+/// Returns `false` if the user already has the song in their favourites.
+pub async fn add_favourite(
+    pool: &PgPool,
+    user_id: i64,
+    favourite: &Favourite,
+) -> anyhow::Result<bool> {
+    let res = sqlx::query!(
+        r#"
+        INSERT INTO favourites (user_id, name, title, url, duration_ms)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT DO NOTHING"#,
+        user_id,
+        favourite.name,
+        favourite.title,
+        favourite.url,
+        favourite.duration_ms
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(res.rows_affected() > 0)
+}
+
+/// Returns the favourites in the order they were added.
+pub async fn get_favourites(pool: &PgPool, user_id: i64) -> anyhow::Result<Vec<Favourite>> {
+    let rec = sqlx::query_as!(
+        Favourite,
+        r#"
+        SELECT name, title, url, duration_ms
+        FROM favourites
+        WHERE user_id = $1
+        ORDER BY id"#,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rec)
+}
+
+/// Removes the favourite at the index, starting at 0, in the order they were added.
+pub async fn remove_favourite(
+    pool: &PgPool,
+    user_id: i64,
+    index: i64,
+) -> anyhow::Result<Option<Favourite>> {
+    let rec = sqlx::query_as!(
+        Favourite,
+        r#"
+        DELETE FROM favourites
+        WHERE id = (
+            SELECT id
+            FROM favourites
+            WHERE user_id = $1
+            ORDER BY id
+            OFFSET $2
+            LIMIT 1
+        )
+        RETURNING name, title, url, duration_ms"#,
+        user_id,
+        index
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(rec)
+}
+
+#[derive(Debug)]
+pub struct RadioStation {
+    pub name: String,
+    pub url: String,
+}
+
+/// Returns `false` if the guild already has a station with that name.
+pub async fn add_radio_station(
+    pool: &PgPool,
+    guild_id: i64,
+    station: &RadioStation,
+) -> anyhow::Result<bool> {
+    let res = sqlx::query!(
+        r#"
+        INSERT INTO radio_stations (guild_id, name, url)
+        VALUES ($1, $2, $3)
+        ON CONFLICT DO NOTHING"#,
+        guild_id,
+        station.name,
+        station.url
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(res.rows_affected() > 0)
+}
+
+/// Returns the stations of the guild sorted by name.
+pub async fn get_radio_stations(pool: &PgPool, guild_id: i64) -> anyhow::Result<Vec<RadioStation>> {
+    let rec = sqlx::query_as!(
+        RadioStation,
+        r#"
+        SELECT name, url
+        FROM radio_stations
+        WHERE guild_id = $1
+        ORDER BY name"#,
+        guild_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rec)
+}
+
+/// Station names are not case sensitive.
+pub async fn get_radio_station(
+    pool: &PgPool,
+    guild_id: i64,
+    name: &str,
+) -> anyhow::Result<Option<RadioStation>> {
+    let rec = sqlx::query_as!(
+        RadioStation,
+        r#"
+        SELECT name, url
+        FROM radio_stations
+        WHERE guild_id = $1 AND LOWER(name) = LOWER($2)"#,
+        guild_id,
+        name
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(rec)
+}
+
+pub async fn remove_radio_station(
+    pool: &PgPool,
+    guild_id: i64,
+    name: &str,
+) -> anyhow::Result<Option<RadioStation>> {
+    let rec = sqlx::query_as!(
+        RadioStation,
+        r#"
+        DELETE FROM radio_stations
+        WHERE guild_id = $1 AND LOWER(name) = LOWER($2)
+        RETURNING name, url"#,
+        guild_id,
+        name
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(rec)
+}
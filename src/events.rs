@@ -1,12 +1,24 @@
-use serenity::{async_trait, model::prelude::*, prelude::*};
+use serenity::{
+    async_trait,
+    model::{interactions::Interaction, prelude::*},
+    prelude::*,
+};
 
 use tracing::{error, info};
 
 use crate::{
+    commands::{
+        queue::{handle_queue_page_button, QUEUE_PAGE_BUTTON},
+        search::{handle_search_select, SEARCH_SELECT},
+    },
     data::{DjOnlyContainer, PoolContainer},
-    db::{delete_guild, delete_user, insert_guild},
+    db::{delete_guild, delete_queue_snapshot, delete_user, insert_guild},
     dj_only_store::delete_guild_from_store,
+    history::save_history,
+    now_playing_panel::{close_panel, handle_panel_button},
     queue::QueueMap,
+    queue_snapshot::restore_queues,
+    slash_commands::handle_slash_command,
 };
 
 pub struct Handler;
@@ -19,14 +31,38 @@ impl Handler {
 
 #[async_trait]
 impl EventHandler for Handler {
-    async fn ready(&self, _: Context, ready: Ready) {
+    async fn ready(&self, ctx: Context, ready: Ready) {
         info!("Connected as {}", ready.user.name);
+
+        let guild_ids: Vec<GuildId> = ready.guilds.iter().map(|guild| guild.id()).collect();
+
+        tokio::spawn(async move {
+            if let Err(e) = restore_queues(&ctx, &guild_ids).await {
+                error!("Could not restore queues: {:?}", e);
+            }
+        });
     }
 
     async fn resume(&self, _: Context, _: ResumedEvent) {
         info!("Resumed");
     }
 
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        match interaction {
+            Interaction::ApplicationCommand(command) => handle_slash_command(&ctx, &command).await,
+            Interaction::MessageComponent(component) => {
+                if component.data.custom_id.starts_with(QUEUE_PAGE_BUTTON) {
+                    handle_queue_page_button(&ctx, &component).await
+                } else if component.data.custom_id.starts_with(SEARCH_SELECT) {
+                    handle_search_select(&ctx, &component).await
+                } else {
+                    handle_panel_button(&ctx, &component).await
+                }
+            }
+            _ => {}
+        }
+    }
+
     async fn guild_delete(&self, ctx: Context, incomplete: GuildUnavailable, _: Option<Guild>) {
         if !incomplete.unavailable {
             info!("Removed from guild: {}", incomplete.id);
@@ -115,6 +151,16 @@ impl EventHandler for Handler {
                             let queue_container = data.get::<QueueMap>().unwrap().clone();
                             let queue = queue_container.remove(&guild_id).unwrap();
                             queue.1.stop();
+                            if let Err(e) = close_panel(&ctx.http, &queue.1).await {
+                                error!("Could not close the now playing panel: {:?}", e);
+                            }
+                            let pool = data.get::<PoolContainer>().unwrap();
+                            if let Err(e) = save_history(pool, guild_id, &queue.1).await {
+                                error!("Could not save history: {:?}", e);
+                            }
+                            if let Err(e) = delete_queue_snapshot(pool, guild_id.into()).await {
+                                error!("Could not remove queue snapshot: {:?}", e);
+                            }
                             let mut handler = handler_lock.lock().await;
                             handler.remove_all_global_events();
                         }
@@ -127,6 +173,16 @@ impl EventHandler for Handler {
                     let queue_container = data.get::<QueueMap>().unwrap().clone();
                     let queue = queue_container.remove(&guild_id).unwrap();
                     queue.1.stop();
+                    if let Err(e) = close_panel(&ctx.http, &queue.1).await {
+                        error!("Could not close the now playing panel: {:?}", e);
+                    }
+                    let pool = data.get::<PoolContainer>().unwrap();
+                    if let Err(e) = save_history(pool, guild_id, &queue.1).await {
+                        error!("Could not save history: {:?}", e);
+                    }
+                    if let Err(e) = delete_queue_snapshot(pool, guild_id.into()).await {
+                        error!("Could not remove queue snapshot: {:?}", e);
+                    }
                     let mut handler = handler_lock.lock().await;
                     handler.remove_all_global_events();
                 }
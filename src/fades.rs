@@ -0,0 +1,153 @@
+use std::{sync::Arc, time::Duration};
+
+use serenity::{async_trait, http::Http, model::id::ChannelId, prelude::Mutex};
+use songbird::{
+    tracks::{LoopState, PlayMode, TrackHandle, TrackResult},
+    Call, Event, EventContext, EventHandler as VoiceEventHandler,
+};
+
+use crate::queue::Queue;
+
+/// Pausing, resuming, skipping and stopping fade for this long so the audio does not click.
+pub const CLICK_FADE: Duration = Duration::from_millis(150);
+
+pub const MAX_CROSSFADE: Duration = Duration::from_secs(12);
+
+/// How often a playing track checks whether it is time to fade into the next one.
+pub const CROSSFADE_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The driver mixes audio in 20ms frames, changing the volume more often is not audible.
+const FADE_STEP: Duration = Duration::from_millis(20);
+
+/// Changes the volume gradually, errors once the track has ended.
+pub async fn fade_volume(
+    handle: &TrackHandle,
+    from: f32,
+    to: f32,
+    length: Duration,
+) -> TrackResult<()> {
+    let steps = (length.as_millis() / FADE_STEP.as_millis()).max(1) as u32;
+
+    for step in 1..=steps {
+        handle.set_volume(from + (to - from) * step as f32 / steps as f32)?;
+        tokio::time::sleep(FADE_STEP).await;
+    }
+
+    Ok(())
+}
+
+/// Fades the track to silence without stopping it.
+pub async fn fade_out(handle: &TrackHandle) -> TrackResult<()> {
+    let volume = handle.get_info().await?.volume;
+
+    fade_volume(handle, volume, 0.0, CLICK_FADE).await
+}
+
+/// The volume is restored once the track is paused, so resuming can fade back in to it.
+pub async fn fade_out_and_pause(handle: &TrackHandle) -> TrackResult<()> {
+    let volume = handle.get_info().await?.volume;
+
+    fade_volume(handle, volume, 0.0, CLICK_FADE).await?;
+    handle.pause()?;
+    handle.set_volume(volume)
+}
+
+pub async fn fade_in_and_play(handle: &TrackHandle) -> TrackResult<()> {
+    let volume = handle.get_info().await?.volume;
+
+    handle.set_volume(0.0)?;
+    handle.play()?;
+    fade_volume(handle, 0.0, volume, CLICK_FADE).await
+}
+
+pub async fn fade_out_and_stop(handle: &TrackHandle) -> TrackResult<()> {
+    fade_out(handle).await?;
+    handle.stop()
+}
+
+/// Starts the preloaded track early and fades between the two once the playing track is about to end.
+/// The queue moves on as soon as the fade starts, so the ending track is no longer the current one.
+pub struct CrossfadeWatcher {
+    pub queue: Queue,
+    pub driver: Arc<Mutex<Call>>,
+    pub chan_id: ChannelId,
+    pub http: Arc<Http>,
+}
+
+#[async_trait]
+impl VoiceEventHandler for CrossfadeWatcher {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        let (state, handle) = match ctx {
+            EventContext::Track(&[(state, handle)]) => (state, handle),
+            _ => return None,
+        };
+
+        let crossfade = self.queue.crossfade();
+
+        // Looping tracks do not end, and live streams have no length to fade out at
+        if crossfade.is_zero()
+            || !matches!(state.playing, PlayMode::Play)
+            || !matches!(state.loops, LoopState::Finite(0))
+        {
+            return None;
+        }
+
+        // The length is in the time of the track, the position is sped up by speed filters
+        let speed = self
+            .queue
+            .find(handle.uuid())
+            .map_or(1.0, |track| track.speed);
+        let length = handle.metadata().duration?.div_f64(speed);
+        let remaining = length.saturating_sub(state.position);
+
+        if remaining > crossfade {
+            return None;
+        }
+
+        // A reloaded track keeps playing for a moment after it was replaced
+        let current_uuid = self
+            .queue
+            .current()
+            .lock()
+            .as_ref()
+            .map(|current| current.uuid());
+
+        if current_uuid != Some(handle.uuid()) {
+            return None;
+        }
+
+        // Without a track to fade into the track just plays to its end
+        let next = self.queue.preloaded_track()?;
+        let next_volume = next.get_info().await.ok()?.volume;
+        let _ = next.set_volume(0.0);
+
+        if !self.queue.advance(handle.uuid()) {
+            let _ = next.set_volume(next_volume);
+            return None;
+        }
+
+        let ending = handle.clone();
+        let volume = state.volume;
+
+        tokio::spawn(async move {
+            let _ = fade_volume(&ending, volume, 0.0, remaining).await;
+            let _ = ending.stop();
+        });
+
+        tokio::spawn(async move {
+            let _ = fade_volume(&next, 0.0, next_volume, crossfade).await;
+        });
+
+        // Loading the tracks after it takes a while, the fades should not wait for that
+        let queue = self.queue.clone();
+        let driver = self.driver.clone();
+        let chan_id = self.chan_id;
+        let http = self.http.clone();
+
+        tokio::spawn(async move {
+            queue.load_upcoming(driver, chan_id, http).await;
+        });
+
+        Some(Event::Cancel)
+    }
+}
@@ -0,0 +1,377 @@
+use std::{ffi::OsString, fmt, mem, time::Duration};
+
+use serenity::async_trait;
+use songbird::input::{
+    error::{Error as InputError, Result as InputResult},
+    ffmpeg_optioned,
+    restartable::Restart,
+    Codec, Container, Input, Metadata,
+};
+use tokio::process::Command;
+
+/// The rate the filters work at, tracks are resampled to it before speeding up or slowing down.
+const SAMPLE_RATE: u32 = 48000;
+
+/// EBU R128 normalisation to the loudness most streaming services aim for.
+const LOUDNORM_FILTER: &str = "loudnorm=I=-14:TP=-1.5:LRA=11";
+
+const SPEED_RANGE: std::ops::RangeInclusive<f32> = 0.5..=2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AudioFilter {
+    BassBoost,
+    Nightcore,
+    Vaporwave,
+    Karaoke,
+    Tremolo,
+    EightD,
+    /// Faster or slower without changing the pitch
+    Tempo(f32),
+    /// Higher or lower without changing the speed
+    Pitch(f32),
+}
+
+impl AudioFilter {
+    /// Tempo and pitch take a factor between 0.5 and 2, the other filters have no value.
+    pub fn parse(name: &str, value: Option<&str>) -> Result<Self, &'static str> {
+        let factor = || match value.map(|value| value.trim_end_matches('x').parse::<f32>()) {
+            Some(Ok(factor)) if SPEED_RANGE.contains(&factor) => Ok(factor),
+            _ => Err("Tempo and pitch need a factor from 0.5 to 2, like 1.25"),
+        };
+
+        match name.to_lowercase().as_str() {
+            "bassboost" | "bass" => Ok(Self::BassBoost),
+            "nightcore" => Ok(Self::Nightcore),
+            "vaporwave" => Ok(Self::Vaporwave),
+            "karaoke" => Ok(Self::Karaoke),
+            "tremolo" => Ok(Self::Tremolo),
+            "8d" => Ok(Self::EightD),
+            "tempo" | "speed" => factor().map(Self::Tempo),
+            "pitch" => factor().map(Self::Pitch),
+            _ => {
+                Err("Must be bassboost, nightcore, vaporwave, karaoke, tremolo, 8d, tempo or pitch")
+            }
+        }
+    }
+
+    /// How much faster than normal the filter makes a track play, pitch is changed without changing the speed.
+    fn speed(&self) -> f64 {
+        match self {
+            Self::Nightcore => 1.25,
+            Self::Vaporwave => 0.8,
+            Self::Tempo(factor) => f64::from(*factor),
+            _ => 1.0,
+        }
+    }
+
+    fn ffmpeg_filter(&self) -> String {
+        let rate = |factor: f32| (SAMPLE_RATE as f32 * factor).round() as u32;
+
+        match self {
+            Self::BassBoost => "bass=g=10:f=110:w=0.6".to_string(),
+            Self::Nightcore => format!(
+                "aresample={SAMPLE_RATE},asetrate={},aresample={SAMPLE_RATE}",
+                rate(1.25)
+            ),
+            Self::Vaporwave => format!(
+                "aresample={SAMPLE_RATE},asetrate={},aresample={SAMPLE_RATE}",
+                rate(0.8)
+            ),
+            // Vocals are usually mixed into the center, subtracting the channels removes them.
+            // Mono sources are turned into stereo first, pan can not read a second channel otherwise.
+            Self::Karaoke => {
+                "aformat=channel_layouts=stereo,pan=stereo|c0=c0-c1|c1=c1-c0".to_string()
+            }
+            Self::Tremolo => "tremolo=f=4:d=0.6".to_string(),
+            Self::EightD => "apulsator=hz=0.125".to_string(),
+            Self::Tempo(factor) => format!("atempo={factor}"),
+            Self::Pitch(factor) => format!(
+                "aresample={SAMPLE_RATE},asetrate={},aresample={SAMPLE_RATE},atempo={}",
+                rate(*factor),
+                1.0 / factor
+            ),
+        }
+    }
+}
+
+impl fmt::Display for AudioFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BassBoost => write!(f, "bass boost"),
+            Self::Nightcore => write!(f, "nightcore"),
+            Self::Vaporwave => write!(f, "vaporwave"),
+            Self::Karaoke => write!(f, "karaoke"),
+            Self::Tremolo => write!(f, "tremolo"),
+            Self::EightD => write!(f, "8D"),
+            Self::Tempo(factor) => write!(f, "tempo {factor}x"),
+            Self::Pitch(factor) => write!(f, "pitch {factor}x"),
+        }
+    }
+}
+
+/// Turns the filter off if it is already on, tempo and pitch are replaced when the factor differs.
+/// Returns whether the filter is on afterwards.
+pub fn toggle_filter(filters: &mut Vec<AudioFilter>, filter: AudioFilter) -> bool {
+    let existing = filters
+        .iter()
+        .position(|active| mem::discriminant(active) == mem::discriminant(&filter));
+
+    match existing {
+        Some(idx) if filters[idx] == filter => {
+            filters.remove(idx);
+            false
+        }
+        Some(idx) => {
+            filters[idx] = filter;
+            true
+        }
+        None => {
+            filters.push(filter);
+            true
+        }
+    }
+}
+
+pub fn format_filters(filters: &[AudioFilter]) -> String {
+    filters
+        .iter()
+        .map(|filter| filter.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The `-af` argument for ffmpeg, `None` when there is nothing to apply.
+/// Loudness normalisation comes last, so it also evens out what the other filters change.
+pub fn filter_chain(filters: &[AudioFilter], normalise: bool) -> Option<String> {
+    let mut chain: Vec<String> = filters
+        .iter()
+        .map(|filter| filter.ffmpeg_filter())
+        .collect();
+
+    if normalise {
+        chain.push(LOUDNORM_FILTER.to_string());
+    }
+
+    if chain.is_empty() {
+        None
+    } else {
+        Some(chain.join(","))
+    }
+}
+
+/// How much faster than normal a track plays with the filters.
+/// Positions of playing tracks are in this sped up time, timestamps in the track itself are not.
+pub fn speed_factor(filters: &[AudioFilter]) -> f64 {
+    filters.iter().map(|filter| filter.speed()).product()
+}
+
+#[derive(Debug)]
+enum Location {
+    /// A file or a link ffmpeg can read directly.
+    Direct(OsString),
+    /// Resolved to a direct link with yt-dlp the first time it is played.
+    Ytdl {
+        search: String,
+        resolved: Option<(String, Metadata)>,
+    },
+}
+
+/// Plays a track through ffmpeg with an audio filter chain,
+/// restarting ffmpeg at the new position when the track is seeked.
+#[derive(Debug)]
+pub struct FilteredSource {
+    location: Location,
+    filter_chain: String,
+    /// See `speed_factor`, seeking is done in the time of the track itself.
+    speed: f64,
+}
+
+impl FilteredSource {
+    pub fn direct(path: impl Into<OsString>, filter_chain: String, speed: f64) -> Self {
+        Self {
+            location: Location::Direct(path.into()),
+            filter_chain,
+            speed,
+        }
+    }
+
+    /// Links are looked up directly, anything else is searched for on youtube.
+    pub fn ytdl(search: &str, filter_chain: String, speed: f64) -> Self {
+        let search = if search.starts_with("http") {
+            search.to_string()
+        } else {
+            format!("ytsearch1:{search}")
+        };
+
+        Self {
+            location: Location::Ytdl {
+                search,
+                resolved: None,
+            },
+            filter_chain,
+            speed,
+        }
+    }
+}
+
+async fn resolve_ytdl(search: &str) -> InputResult<(String, Metadata)> {
+    let output = Command::new("yt-dlp")
+        .args([
+            "-j",
+            "-f",
+            "webm[abr>0]/bestaudio/best",
+            "--no-playlist",
+            search,
+        ])
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(InputError::YouTubeDlRun(output));
+    }
+
+    let value: serde_json::Value =
+        serde_json::from_slice(&output.stdout).map_err(|error| InputError::Json {
+            error,
+            parsed_text: String::from_utf8_lossy(&output.stdout).to_string(),
+        })?;
+
+    match value["url"].as_str() {
+        Some(url) => Ok((url.to_string(), Metadata::from_ytdl_output(value.clone()))),
+        None => Err(InputError::YouTubeDlUrl(value)),
+    }
+}
+
+#[async_trait]
+impl Restart for FilteredSource {
+    async fn call_restart(&mut self, time: Option<Duration>) -> InputResult<Input> {
+        let (path, metadata) = match &mut self.location {
+            Location::Direct(path) => (path.clone(), None),
+            Location::Ytdl { search, resolved } => {
+                if resolved.is_none() {
+                    *resolved = Some(resolve_ytdl(search).await?);
+                }
+
+                match resolved {
+                    Some((url, metadata)) => (OsString::from(url.clone()), Some(metadata.clone())),
+                    None => return Err(InputError::Metadata),
+                }
+            }
+        };
+
+        let mut pre_input_args = Vec::new();
+
+        if path.to_string_lossy().starts_with("http") {
+            pre_input_args.extend([
+                "-reconnect",
+                "1",
+                "-reconnect_streamed",
+                "1",
+                "-reconnect_delay_max",
+                "5",
+            ]);
+        }
+
+        let start = time.map(|time| format!("{:.3}", time.as_secs_f64() * self.speed));
+
+        if let Some(start) = &start {
+            pre_input_args.extend(["-ss", start.as_str()]);
+        }
+
+        let args = [
+            "-af",
+            &self.filter_chain,
+            "-f",
+            "s16le",
+            "-ac",
+            "2",
+            "-ar",
+            "48000",
+            "-acodec",
+            "pcm_f32le",
+            "-",
+        ];
+
+        let mut input = ffmpeg_optioned(path, &pre_input_args, &args).await?;
+
+        // The filters always put out stereo, even for mono files
+        input.stereo = true;
+
+        if let Some(metadata) = metadata {
+            input.metadata = Box::new(metadata);
+        }
+
+        Ok(input)
+    }
+
+    async fn lazy_init(&mut self) -> InputResult<(Option<Metadata>, Codec, Container)> {
+        Ok((None, Codec::FloatPcm, Container::Raw))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{filter_chain, speed_factor, toggle_filter, AudioFilter};
+
+    #[test]
+    fn test_parse_filter() {
+        assert_eq!(
+            AudioFilter::parse("BassBoost", None),
+            Ok(AudioFilter::BassBoost)
+        );
+        assert_eq!(AudioFilter::parse("8d", None), Ok(AudioFilter::EightD));
+        assert_eq!(
+            AudioFilter::parse("tempo", Some("1.25x")),
+            Ok(AudioFilter::Tempo(1.25))
+        );
+        assert_eq!(
+            AudioFilter::parse("pitch", Some("0.8")),
+            Ok(AudioFilter::Pitch(0.8))
+        );
+        assert!(AudioFilter::parse("tempo", None).is_err());
+        assert!(AudioFilter::parse("tempo", Some("3")).is_err());
+        assert!(AudioFilter::parse("echo", None).is_err());
+    }
+
+    #[test]
+    fn test_toggle_filter() {
+        let mut filters = Vec::new();
+
+        assert!(toggle_filter(&mut filters, AudioFilter::BassBoost));
+        assert!(toggle_filter(&mut filters, AudioFilter::Tempo(1.5)));
+        assert!(toggle_filter(&mut filters, AudioFilter::Tempo(0.75)));
+        assert_eq!(
+            filters,
+            vec![AudioFilter::BassBoost, AudioFilter::Tempo(0.75)]
+        );
+
+        assert!(!toggle_filter(&mut filters, AudioFilter::BassBoost));
+        assert_eq!(filters, vec![AudioFilter::Tempo(0.75)]);
+    }
+
+    #[test]
+    fn test_speed_factor() {
+        assert_eq!(speed_factor(&[]), 1.0);
+        assert_eq!(speed_factor(&[AudioFilter::Pitch(2.0)]), 1.0);
+        assert_eq!(
+            speed_factor(&[AudioFilter::Nightcore, AudioFilter::Tempo(2.0)]),
+            2.5
+        );
+    }
+
+    #[test]
+    fn test_filter_chain() {
+        assert_eq!(filter_chain(&[], false), None);
+        assert_eq!(
+            filter_chain(&[], true),
+            Some("loudnorm=I=-14:TP=-1.5:LRA=11".to_string())
+        );
+        assert_eq!(
+            filter_chain(&[AudioFilter::Tremolo, AudioFilter::Pitch(2.0)], false),
+            Some(
+                "tremolo=f=4:d=0.6,aresample=48000,asetrate=96000,aresample=48000,atempo=0.5"
+                    .to_string()
+            )
+        );
+    }
+}
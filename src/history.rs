@@ -0,0 +1,83 @@
+use std::{
+    convert::{TryFrom, TryInto},
+    time::Duration,
+};
+
+use serenity::model::prelude::*;
+use sqlx::PgPool;
+
+use crate::{
+    db::{add_history_entries, get_history, HistoryEntry},
+    queue::{Queue, QueuedTrack, HISTORY_LENGTH},
+};
+
+fn to_history_entry(track: QueuedTrack) -> HistoryEntry {
+    HistoryEntry {
+        name: track.name,
+        title: track.title,
+        url: track.url,
+        duration_ms: track
+            .duration
+            .map(|duration| i64::try_from(duration.as_millis()).unwrap_or_default()),
+        requester_id: track.requester.map(|requester| requester.into()),
+    }
+}
+
+fn from_history_entry(entry: HistoryEntry) -> QueuedTrack {
+    let requester = entry
+        .requester_id
+        .and_then(|requester_id| requester_id.try_into().ok())
+        .map(UserId);
+
+    QueuedTrack {
+        title: entry.title,
+        url: entry.url,
+        duration: entry
+            .duration_ms
+            .and_then(|duration_ms| duration_ms.try_into().ok())
+            .map(Duration::from_millis),
+        ..QueuedTrack::new(entry.name, requester)
+    }
+}
+
+/// Writes the tracks played since the last save to the database.
+pub async fn save_history(pool: &PgPool, guild_id: GuildId, queue: &Queue) -> anyhow::Result<()> {
+    let entries: Vec<HistoryEntry> = queue
+        .take_unsaved_history()
+        .into_iter()
+        .map(to_history_entry)
+        .collect();
+
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    add_history_entries(pool, guild_id.into(), &entries, HISTORY_LENGTH as i64).await
+}
+
+/// The history saved in the database, for guilds without a queue.
+pub async fn load_saved_history(
+    pool: &PgPool,
+    guild_id: GuildId,
+) -> anyhow::Result<Vec<QueuedTrack>> {
+    let history = get_history(pool, guild_id.into(), HISTORY_LENGTH as i64).await?;
+
+    Ok(history.into_iter().map(from_history_entry).collect())
+}
+
+/// Returns the recently played tracks, the newest first.
+/// The first time this is called for a queue the history is read from the database,
+/// so it includes tracks from before a restart.
+pub async fn load_history(
+    pool: &PgPool,
+    guild_id: GuildId,
+    queue: &Queue,
+) -> anyhow::Result<Vec<QueuedTrack>> {
+    if !queue.history_loaded() {
+        save_history(pool, guild_id, queue).await?;
+
+        queue.set_history(load_saved_history(pool, guild_id).await?);
+    }
+
+    Ok(queue.history())
+}
@@ -0,0 +1,175 @@
+use std::fmt::Display;
+
+use serenity::{
+    builder::{CreateComponents, CreateEmbed},
+    model::{
+        interactions::{
+            application_command::ApplicationCommandInteraction,
+            message_component::MessageComponentInteraction,
+            InteractionApplicationCommandCallbackDataFlags,
+        },
+        prelude::*,
+    },
+    prelude::*,
+};
+
+/// Where a command was run from, either a prefixed message, a slash command or a button.
+/// Interactions have to be deferred before any of the reply methods are used,
+/// replies to buttons are only shown to the user who pressed them.
+#[derive(Clone, Copy)]
+pub enum Invoker<'a> {
+    Message(&'a Message),
+    Interaction(&'a ApplicationCommandInteraction),
+    Component(&'a MessageComponentInteraction),
+}
+
+impl<'a> From<&'a Message> for Invoker<'a> {
+    fn from(msg: &'a Message) -> Self {
+        Self::Message(msg)
+    }
+}
+
+impl<'a> From<&'a ApplicationCommandInteraction> for Invoker<'a> {
+    fn from(command: &'a ApplicationCommandInteraction) -> Self {
+        Self::Interaction(command)
+    }
+}
+
+impl<'a> From<&'a MessageComponentInteraction> for Invoker<'a> {
+    fn from(component: &'a MessageComponentInteraction) -> Self {
+        Self::Component(component)
+    }
+}
+
+impl Invoker<'_> {
+    pub fn guild_id(&self) -> GuildId {
+        match self {
+            Self::Message(msg) => msg.guild_id.unwrap(),
+            Self::Interaction(command) => command.guild_id.unwrap(),
+            Self::Component(component) => component.guild_id.unwrap(),
+        }
+    }
+
+    pub fn channel_id(&self) -> ChannelId {
+        match self {
+            Self::Message(msg) => msg.channel_id,
+            Self::Interaction(command) => command.channel_id,
+            Self::Component(component) => component.channel_id,
+        }
+    }
+
+    pub fn author_id(&self) -> UserId {
+        match self {
+            Self::Message(msg) => msg.author.id,
+            Self::Interaction(command) => command.user.id,
+            Self::Component(component) => component.user.id,
+        }
+    }
+
+    pub async fn guild(&self, ctx: &Context) -> Guild {
+        self.guild_id().to_guild_cached(ctx).await.unwrap()
+    }
+
+    pub async fn say(&self, ctx: &Context, content: impl Display) -> serenity::Result<Message> {
+        match self {
+            Self::Message(msg) => msg.channel_id.say(ctx, content).await,
+            Self::Interaction(command) => {
+                command
+                    .create_followup_message(ctx, |f| f.content(content))
+                    .await
+            }
+            Self::Component(component) => {
+                component
+                    .create_followup_message(ctx, |f| {
+                        f.content(content)
+                            .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
+                    })
+                    .await
+            }
+        }
+    }
+
+    /// Pings the author for prefixed commands, slash command responses are already attached to the author.
+    pub async fn reply(&self, ctx: &Context, content: impl Display) -> serenity::Result<Message> {
+        match self {
+            Self::Message(msg) => msg.reply_ping(ctx, content).await,
+            Self::Interaction(_) | Self::Component(_) => self.say(ctx, content).await,
+        }
+    }
+
+    pub async fn send_embed<F>(&self, ctx: &Context, f: F) -> serenity::Result<Message>
+    where
+        F: FnOnce(&mut CreateEmbed) -> &mut CreateEmbed,
+    {
+        match self {
+            Self::Message(msg) => msg.channel_id.send_message(ctx, |m| m.embed(f)).await,
+            Self::Interaction(command) => {
+                command
+                    .create_followup_message(ctx, |m| m.create_embed(f))
+                    .await
+            }
+            Self::Component(component) => {
+                component
+                    .create_followup_message(ctx, |m| {
+                        m.create_embed(f)
+                            .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
+                    })
+                    .await
+            }
+        }
+    }
+
+    /// Replies to buttons are ephemeral, so they can only be edited through the interaction.
+    pub async fn edit_embed<F>(
+        &self,
+        ctx: &Context,
+        msg: &mut Message,
+        f: F,
+    ) -> serenity::Result<()>
+    where
+        F: FnOnce(&mut CreateEmbed) -> &mut CreateEmbed,
+    {
+        match self {
+            Self::Message(_) | Self::Interaction(_) => msg.edit(ctx, |m| m.embed(f)).await,
+            Self::Component(component) => {
+                *msg = component
+                    .edit_followup_message(ctx, msg.id, |m| m.create_embed(f))
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn send_embed_with_components<F, C>(
+        &self,
+        ctx: &Context,
+        f: F,
+        c: C,
+    ) -> serenity::Result<Message>
+    where
+        F: FnOnce(&mut CreateEmbed) -> &mut CreateEmbed,
+        C: FnOnce(&mut CreateComponents) -> &mut CreateComponents,
+    {
+        match self {
+            Self::Message(msg) => {
+                msg.channel_id
+                    .send_message(ctx, |m| m.embed(f).components(c))
+                    .await
+            }
+            Self::Interaction(command) => {
+                command
+                    .create_followup_message(ctx, |m| m.create_embed(f).components(c))
+                    .await
+            }
+            Self::Component(component) => {
+                component
+                    .create_followup_message(ctx, |m| {
+                        m.create_embed(f)
+                            .components(c)
+                            .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
+                    })
+                    .await
+            }
+        }
+    }
+}
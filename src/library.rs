@@ -0,0 +1,318 @@
+use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use serde::Deserialize;
+use serenity::{model::id::UserId, prelude::*};
+use tokio::process::Command;
+use tracing::warn;
+
+use crate::queue::QueuedTrack;
+
+lazy_static::lazy_static! {
+    /// The owner's music library, set with `MUSIC_LIBRARY_DIR` in the dotenv file.
+    static ref LIBRARY_DIR: Option<PathBuf> = std::env::var("MUSIC_LIBRARY_DIR")
+        .ok()
+        .and_then(|dir| fs::canonicalize(dir).ok());
+}
+
+const AUDIO_EXTENSIONS: [&str; 4] = ["mp3", "ogg", "flac", "wav"];
+
+/// Queued tracks with names starting with this are played from the library.
+const LIBRARY_PREFIX: &str = "library:";
+
+/// How many files ffprobe reads at the same time when scanning the library.
+const PROBE_CONCURRENCY: usize = 8;
+
+const ATTACHMENT_HOSTS: [&str; 2] = [
+    "https://cdn.discordapp.com/attachments/",
+    "https://media.discordapp.net/attachments/",
+];
+
+#[derive(Debug, Clone)]
+pub struct LibraryTrack {
+    /// Relative to the library directory, always separated by `/`.
+    pub path: String,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub duration: Option<Duration>,
+}
+
+impl LibraryTrack {
+    pub fn display_title(&self) -> String {
+        let title = self.title.as_deref().unwrap_or(&self.path);
+
+        match &self.artist {
+            Some(artist) => format!("{artist} - {title}"),
+            None => title.to_string(),
+        }
+    }
+
+    pub fn to_queued_track(&self, requester: UserId) -> QueuedTrack {
+        QueuedTrack {
+            title: Some(self.title.clone().unwrap_or_else(|| self.path.clone())),
+            uploader: self.artist.clone(),
+            duration: self.duration,
+            ..QueuedTrack::new(format!("{LIBRARY_PREFIX}{}", self.path), Some(requester))
+        }
+    }
+
+    fn matches(&self, words: &[String]) -> bool {
+        let haystack = format!(
+            "{} {} {}",
+            self.title.as_deref().unwrap_or_default(),
+            self.artist.as_deref().unwrap_or_default(),
+            self.path
+        )
+        .to_lowercase();
+
+        words.iter().all(|word| haystack.contains(word))
+    }
+}
+
+/// The scanned library, `None` until it is first used or rescanned.
+pub struct LibraryCache;
+
+impl TypeMapKey for LibraryCache {
+    type Value = Arc<RwLock<Option<Vec<LibraryTrack>>>>;
+}
+
+pub fn is_audio_file(file_name: &str) -> bool {
+    Path::new(file_name)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map_or(false, |extension| {
+            AUDIO_EXTENSIONS.contains(&extension.to_lowercase().as_str())
+        })
+}
+
+/// Audio files attached to discord messages are streamed directly instead of through yt-dlp.
+pub fn is_audio_attachment(url: &str) -> bool {
+    let path = url.split('?').next().unwrap_or_default();
+
+    ATTACHMENT_HOSTS.iter().any(|host| path.starts_with(host)) && is_audio_file(path)
+}
+
+/// The file a `library:` track refers to, as long as it is an audio file inside the library.
+pub fn get_library_path(name: &str) -> Option<PathBuf> {
+    let relative = name.strip_prefix(LIBRARY_PREFIX)?;
+    let dir = LIBRARY_DIR.as_ref()?;
+
+    let path = fs::canonicalize(dir.join(relative)).ok()?;
+
+    if path.starts_with(dir) && is_audio_file(relative) {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Every word of the query has to be in the title, artist or path of the track.
+pub fn search_library<'a>(tracks: &'a [LibraryTrack], query: &str) -> Vec<&'a LibraryTrack> {
+    let words: Vec<String> = query
+        .split_whitespace()
+        .map(|word| word.to_lowercase())
+        .collect();
+
+    tracks
+        .iter()
+        .filter(|track| track.matches(&words))
+        .collect()
+}
+
+fn find_audio_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            find_audio_files(&path, files)?;
+        } else if path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map_or(false, is_audio_file)
+        {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ProbeResponse {
+    #[serde(default)]
+    format: ProbeSection,
+    #[serde(default)]
+    streams: Vec<ProbeSection>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ProbeSection {
+    duration: Option<String>,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+}
+
+impl ProbeResponse {
+    /// Tags are in the format for mp3 and flac, but in the stream for ogg, and the case of the keys differs.
+    fn tag(&self, key: &str) -> Option<String> {
+        std::iter::once(&self.format)
+            .chain(&self.streams)
+            .flat_map(|section| &section.tags)
+            .find(|(tag, _)| tag.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.clone())
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        self.format
+            .duration
+            .as_deref()
+            .and_then(|duration| duration.parse().ok())
+            .map(Duration::from_secs_f64)
+    }
+}
+
+async fn probe_file(path: impl AsRef<OsStr>) -> anyhow::Result<ProbeResponse> {
+    let output = Command::new("ffprobe")
+        .args([
+            "-v",
+            "quiet",
+            "-print_format",
+            "json",
+            "-show_format",
+            "-show_streams",
+        ])
+        .arg(path)
+        .output()
+        .await?;
+
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+/// Finds every audio file in the library and reads their tags.
+pub async fn scan_library() -> anyhow::Result<Vec<LibraryTrack>> {
+    let dir = match LIBRARY_DIR.as_ref() {
+        Some(dir) => dir.clone(),
+        None => return Ok(Vec::new()),
+    };
+
+    let files = {
+        let dir = dir.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let mut files = Vec::new();
+            find_audio_files(&dir, &mut files).map(|_| files)
+        })
+        .await??
+    };
+
+    let files: Vec<(PathBuf, String)> = files
+        .into_iter()
+        .filter_map(|file| {
+            let path = file.strip_prefix(&dir).ok()?.to_str()?.replace('\\', "/");
+            Some((file, path))
+        })
+        .collect();
+
+    let mut tracks = Vec::with_capacity(files.len());
+
+    for chunk in files.chunks(PROBE_CONCURRENCY) {
+        let probes: Vec<_> = chunk
+            .iter()
+            .map(|(file, _)| {
+                let file = file.clone();
+                tokio::spawn(async move { probe_file(&file).await })
+            })
+            .collect();
+
+        for ((_, path), probe) in chunk.iter().zip(probes) {
+            let probe = probe.await?.unwrap_or_else(|e| {
+                warn!("Could not read the tags of {}, {:?}", path, e);
+                ProbeResponse::default()
+            });
+
+            tracks.push(LibraryTrack {
+                path: path.clone(),
+                title: probe.tag("title"),
+                artist: probe.tag("artist"),
+                duration: probe.duration(),
+            });
+        }
+    }
+
+    tracks.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(tracks)
+}
+
+/// The length of an audio file at a link, `None` if ffprobe could not read it.
+pub async fn probe_duration(url: &str) -> Option<Duration> {
+    match probe_file(url).await {
+        Ok(probe) => probe.duration(),
+        Err(e) => {
+            warn!("Could not read the length of {}, {:?}", url, e);
+            None
+        }
+    }
+}
+
+pub fn library_configured() -> bool {
+    LIBRARY_DIR.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_audio_attachment, search_library, LibraryTrack};
+
+    fn track(path: &str, title: Option<&str>, artist: Option<&str>) -> LibraryTrack {
+        LibraryTrack {
+            path: path.to_string(),
+            title: title.map(|title| title.to_string()),
+            artist: artist.map(|artist| artist.to_string()),
+            duration: None,
+        }
+    }
+
+    #[test]
+    fn test_search_library() {
+        let tracks = vec![
+            track("rock/song.mp3", Some("Bohemian Rhapsody"), Some("Queen")),
+            track("pop/other.flac", Some("Thriller"), Some("Michael Jackson")),
+            track("untagged/queen live.ogg", None, None),
+        ];
+
+        let paths = |query| {
+            search_library(&tracks, query)
+                .into_iter()
+                .map(|track| track.path.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            paths("queen"),
+            vec!["rock/song.mp3", "untagged/queen live.ogg"]
+        );
+        assert_eq!(paths("rhapsody QUEEN"), vec!["rock/song.mp3"]);
+        assert_eq!(paths("jackson flac"), vec!["pop/other.flac"]);
+        assert!(paths("beatles").is_empty());
+    }
+
+    #[test]
+    fn test_audio_attachment() {
+        assert!(is_audio_attachment(
+            "https://cdn.discordapp.com/attachments/1/2/song.MP3"
+        ));
+        assert!(is_audio_attachment(
+            "https://media.discordapp.net/attachments/1/2/song.ogg?width=0"
+        ));
+        assert!(!is_audio_attachment(
+            "https://cdn.discordapp.com/attachments/1/2/image.png"
+        ));
+        assert!(!is_audio_attachment("https://example.com/song.mp3"));
+    }
+}
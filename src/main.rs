@@ -1,3 +1,4 @@
+mod autoplay;
 mod checks;
 mod commands;
 mod consts;
@@ -5,9 +6,21 @@ mod data;
 mod db;
 mod dj_only_store;
 mod events;
+mod fades;
+mod filters;
+mod history;
+mod invoker;
+mod library;
 mod lyrics_api;
+mod now_playing_panel;
 mod playlists;
 mod queue;
+mod queue_limits;
+mod queue_settings;
+mod queue_snapshot;
+mod radio;
+mod skip_threshold;
+mod slash_commands;
 mod voice_events;
 
 use bb8_redis::{bb8, RedisConnectionManager};
@@ -40,14 +53,20 @@ use std::{collections::HashSet, env, time::Duration};
 use tracing_subscriber::{EnvFilter, FmtSubscriber};
 
 use commands::{
-    db_testing::*, dj_only::*, help::*, info::*, join::*, loop_command::*, lyrics::*, mute::*,
-    now_playing::*, pause::*, perms::*, ping::*, play::*, prefix::*, queue::*, remove::*,
-    restart::*, resume::*, shuffle::*, skip::*, stop::*, volume::*,
+    autoplay::*, crossfade::*, db_testing::*, dj_only::*, fair_queue::*, favourites::*, filter::*,
+    help::*, history::*, info::*, join::*, library::*, loop_command::*, lyrics::*, move_command::*,
+    mute::*, normalise::*, now_playing::*, pause::*, perms::*, ping::*, play::*, play_next::*,
+    playlist::*, prefix::*, queue::*, queue_limits::*, remove::*, restart::*, resume::*, search::*,
+    seek::*, shuffle::*, skip::*, skip_to::*, slash::*, station::*, stop::*, volume::*,
+    voteskip::*,
 };
 
+use commands::search::SearchResultsMap;
 use data::*;
 use events::Handler;
+use library::LibraryCache;
 use queue::QueueMap;
+use queue_snapshot::{save_queue_snapshots, SNAPSHOT_INTERVAL};
 
 use mimalloc::MiMalloc;
 
@@ -60,15 +79,37 @@ static GLOBAL: MiMalloc = MiMalloc;
     join,
     mute,
     play,
+    play_next,
+    search,
     skip,
+    skip_to,
+    voteskip,
     stop,
     loop_command,
+    autoplay,
     remove,
+    remove_dupes,
+    remove_user,
+    clear,
+    move_command,
+    swap,
     volume,
+    filter,
     pause,
     resume,
     restart,
+    seek,
+    forward,
+    rewind,
     queue,
+    history,
+    previous,
+    replay,
+    playlist,
+    library,
+    station,
+    grab,
+    favourites,
     now_playing,
     shuffle,
     donate,
@@ -87,12 +128,22 @@ struct General;
     insert_all_guilds,
     sys_info,
     in_voice_channel,
-    guild_count
+    guild_count,
+    slash_commands
 )]
 struct Owner;
 
 #[group]
-#[commands(perms, dj_only, prefix)]
+#[commands(
+    perms,
+    dj_only,
+    fair_queue,
+    normalise,
+    crossfade,
+    queue_limits,
+    prefix,
+    skip_threshold
+)]
 struct Moderation;
 
 #[hook]
@@ -157,7 +208,7 @@ async fn main() -> anyhow::Result<()> {
 
     let http = Http::new_with_token(&token);
 
-    let owners = match http.get_current_application_info().await {
+    let (owners, application_id) = match http.get_current_application_info().await {
         Ok(info) => {
             let mut owners = HashSet::new();
             owners.insert(info.owner.id);
@@ -167,7 +218,7 @@ async fn main() -> anyhow::Result<()> {
                 }
             }
 
-            owners
+            (owners, info.id)
         }
         Err(why) => panic!("Could not access application info: {:?}", why),
     };
@@ -215,6 +266,7 @@ async fn main() -> anyhow::Result<()> {
         .group(&OWNER_GROUP);
 
     let mut client = Client::builder(&token)
+        .application_id(application_id.0)
         .framework(framework)
         .event_handler(Handler::new())
         .register_songbird()
@@ -233,15 +285,37 @@ async fn main() -> anyhow::Result<()> {
         data.insert::<ReqwestClientContainer>(Default::default());
         data.insert::<DjOnlyContainer>(redis_pool);
         data.insert::<QueueMap>(Default::default());
+        data.insert::<SearchResultsMap>(Default::default());
+        data.insert::<LibraryCache>(Default::default());
         data.insert::<PrefixCache>(Default::default());
     }
 
     let shard_manager = client.shard_manager.clone();
+    let data = client.data.clone();
+    let cache = client.cache_and_http.cache.clone();
+
+    {
+        let data = data.clone();
+        let cache = cache.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SNAPSHOT_INTERVAL);
+            loop {
+                interval.tick().await;
+                let data = data.read().await;
+                save_queue_snapshots(&data, &cache, false).await;
+            }
+        });
+    }
 
     tokio::spawn(async move {
         tokio::signal::ctrl_c()
             .await
             .expect("Could not register ctrl+c handler");
+        {
+            let data = data.read().await;
+            save_queue_snapshots(&data, &cache, true).await;
+        }
         shard_manager.lock().await.shutdown_all().await;
     });
 
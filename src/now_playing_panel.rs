@@ -0,0 +1,324 @@
+use std::time::Duration;
+
+use serenity::{
+    builder::{CreateComponents, CreateEmbed},
+    framework::standard::Reason,
+    http::Http,
+    model::{
+        interactions::{
+            message_component::{ButtonStyle, MessageComponentInteraction},
+            InteractionResponseType,
+        },
+        prelude::*,
+    },
+    prelude::*,
+    utils::Color,
+};
+use songbird::tracks::{PlayMode, TrackHandle, TrackState};
+use tracing::{info, warn};
+
+use crate::{
+    checks::{check_dj_only, check_not_blacklisted},
+    commands::{
+        favourites::grab_track,
+        loop_command::cycle_repeat_mode,
+        pause::toggle_pause,
+        shuffle::shuffle_tracks,
+        skip::skip_track,
+        stop::stop_playing,
+        util::{format_duration_to_mm_ss, format_position_bar, format_track_length},
+        volume::change_volume,
+    },
+    consts::INSUFFICIENT_PERMISSIONS_MESSAGE,
+    invoker::Invoker,
+    queue::{Queue, QueueMap, QueuedTrack, RepeatMode},
+};
+
+pub const PANEL_UPDATE_INTERVAL: Duration = Duration::from_secs(15);
+
+const VOLUME_STEP: i32 = 10;
+
+const PAUSE_BUTTON: &str = "panel_pause";
+const SKIP_BUTTON: &str = "panel_skip";
+const STOP_BUTTON: &str = "panel_stop";
+const LOOP_BUTTON: &str = "panel_loop";
+const SHUFFLE_BUTTON: &str = "panel_shuffle";
+const VOLUME_DOWN_BUTTON: &str = "panel_volume_down";
+const VOLUME_UP_BUTTON: &str = "panel_volume_up";
+const GRAB_BUTTON: &str = "panel_grab";
+
+fn panel_embed<'a>(
+    e: &'a mut CreateEmbed,
+    handle: &TrackHandle,
+    state: &TrackState,
+    repeat_mode: RepeatMode,
+    queued_track: Option<&QueuedTrack>,
+    up_next: Option<&QueuedTrack>,
+) -> &'a mut CreateEmbed {
+    let metadata = handle.metadata();
+    // Radio stations played through ffmpeg only have what was saved when they were queued
+    let title = metadata
+        .title
+        .clone()
+        .or_else(|| queued_track.map(|track| track.display_title().to_string()))
+        .unwrap_or_default();
+    let url = metadata
+        .source_url
+        .clone()
+        .or_else(|| queued_track.and_then(|track| track.url.clone()))
+        .unwrap_or_default();
+
+    e.title("Now playing");
+    e.description(format!("[{title}]({url})"));
+
+    if let Some(stream_title) = queued_track.and_then(|track| track.stream_title.as_ref()) {
+        e.field("On air", stream_title, false);
+    }
+
+    if queued_track.map_or(false, |track| track.live) {
+        e.field(
+            "Position",
+            format!("🔴 LIVE `{}`", format_duration_to_mm_ss(state.position)),
+            false,
+        );
+    } else if let Some(length) = metadata.duration {
+        e.field(
+            "Position",
+            format!(
+                "{} `{} / {}`",
+                format_position_bar(state.position, length),
+                format_duration_to_mm_ss(state.position),
+                format_duration_to_mm_ss(length)
+            ),
+            false,
+        );
+    }
+
+    e.field("Repeat", repeat_mode, true);
+    e.field("Volume", (state.volume * 100f32).round(), true);
+
+    if let Some(uploader) = &metadata.channel {
+        e.field("Uploader", uploader, true);
+    }
+
+    if let Some(requester) = queued_track.and_then(|track| track.requester) {
+        e.field("Requested by", requester.mention(), true);
+    }
+
+    if let Some(up_next) = up_next {
+        e.field(
+            "Up next",
+            format!(
+                "{} `{}`",
+                up_next.display_title(),
+                format_track_length(up_next)
+            ),
+            false,
+        );
+    }
+
+    if let Some(thumbnail) = &metadata.thumbnail {
+        e.thumbnail(thumbnail);
+    }
+
+    e.color(Color::DARK_GREEN);
+
+    e
+}
+
+fn panel_components(c: &mut CreateComponents, paused: bool) -> &mut CreateComponents {
+    c.create_action_row(|r| {
+        r.create_button(|b| {
+            b.custom_id(PAUSE_BUTTON)
+                .style(ButtonStyle::Primary)
+                .label(if paused { "Resume" } else { "Pause" })
+        })
+        .create_button(|b| {
+            b.custom_id(SKIP_BUTTON)
+                .style(ButtonStyle::Secondary)
+                .label("Skip")
+        })
+        .create_button(|b| {
+            b.custom_id(STOP_BUTTON)
+                .style(ButtonStyle::Danger)
+                .label("Stop")
+        })
+        .create_button(|b| {
+            b.custom_id(GRAB_BUTTON)
+                .style(ButtonStyle::Success)
+                .label("Grab")
+        })
+    })
+    .create_action_row(|r| {
+        r.create_button(|b| {
+            b.custom_id(LOOP_BUTTON)
+                .style(ButtonStyle::Secondary)
+                .label("Repeat")
+        })
+        .create_button(|b| {
+            b.custom_id(SHUFFLE_BUTTON)
+                .style(ButtonStyle::Secondary)
+                .label("Shuffle")
+        })
+        .create_button(|b| {
+            b.custom_id(VOLUME_DOWN_BUTTON)
+                .style(ButtonStyle::Secondary)
+                .label("Volume -")
+        })
+        .create_button(|b| {
+            b.custom_id(VOLUME_UP_BUTTON)
+                .style(ButtonStyle::Secondary)
+                .label("Volume +")
+        })
+    })
+}
+
+/// Edits the queue's now playing message to show the given track, or sends a new one if there is none yet.
+pub async fn update_panel(
+    http: &Http,
+    queue: &Queue,
+    chan_id: ChannelId,
+    handle: &TrackHandle,
+    state: &TrackState,
+) -> anyhow::Result<()> {
+    let paused = matches!(state.playing, PlayMode::Pause);
+    let repeat_mode = queue.repeat_mode();
+
+    let tracks = queue.current_queue();
+    let queued_track = tracks.iter().find(|track| track.uuid == handle.uuid());
+    let up_next = tracks.iter().find(|track| track.uuid != handle.uuid());
+
+    if let Some(message_id) = queue.panel_message() {
+        let edited = chan_id
+            .edit_message(http, message_id, |m| {
+                m.embed(|e| panel_embed(e, handle, state, repeat_mode, queued_track, up_next))
+                    .components(|c| panel_components(c, paused))
+            })
+            .await;
+
+        if edited.is_ok() {
+            return Ok(());
+        }
+    }
+
+    let message = chan_id
+        .send_message(http, |m| {
+            m.embed(|e| panel_embed(e, handle, state, repeat_mode, queued_track, up_next))
+                .components(|c| panel_components(c, paused))
+        })
+        .await?;
+
+    queue.set_panel_message(Some(message.id));
+
+    Ok(())
+}
+
+pub async fn refresh_panel(http: &Http, queue: &Queue) -> anyhow::Result<()> {
+    let chan_id = match queue.text_channel() {
+        Some(chan_id) => chan_id,
+        None => return Ok(()),
+    };
+
+    let current = { queue.current().lock().clone() };
+
+    match current {
+        Some(handle) => {
+            let state = handle.get_info().await?;
+            update_panel(http, queue, chan_id, &handle, &state).await
+        }
+        None => close_panel(http, queue).await,
+    }
+}
+
+/// Removes the buttons from the now playing message once nothing is playing anymore.
+pub async fn close_panel(http: &Http, queue: &Queue) -> anyhow::Result<()> {
+    if let (Some(chan_id), Some(message_id)) = (queue.text_channel(), queue.panel_message()) {
+        chan_id
+            .edit_message(http, message_id, |m| {
+                m.embed(|e| e.title("Stopped playing").color(Color::DARK_GREEN))
+                    .components(|c| c)
+            })
+            .await?;
+
+        queue.set_panel_message(None);
+    }
+
+    Ok(())
+}
+
+pub async fn handle_panel_button(ctx: &Context, component: &MessageComponentInteraction) {
+    let guild_id = match component.guild_id {
+        Some(guild_id) => guild_id,
+        None => return,
+    };
+
+    if let Err(e) = component
+        .create_interaction_response(ctx, |r| {
+            r.kind(InteractionResponseType::DeferredUpdateMessage)
+        })
+        .await
+    {
+        warn!(
+            "Could not respond to button {}, {:?}",
+            component.data.custom_id, e
+        );
+        return;
+    }
+
+    let invoker = Invoker::from(component);
+
+    // Grabbing only sends the song to the user, so it does not need dj permissions
+    let check = if component.data.custom_id == GRAB_BUTTON {
+        check_not_blacklisted(ctx, guild_id, component.user.id).await
+    } else {
+        check_dj_only(ctx, guild_id, component.user.id).await
+    };
+
+    if let Err(reason) = check {
+        let content = match reason {
+            Reason::User(reason) => reason,
+            reason => {
+                info!("{:?}", reason);
+                INSUFFICIENT_PERMISSIONS_MESSAGE.to_string()
+            }
+        };
+
+        let _ = invoker.say(ctx, content).await;
+        return;
+    }
+
+    let queue_container = {
+        let data = ctx.data.read().await;
+        data.get::<QueueMap>().unwrap().clone()
+    };
+
+    if !queue_container.contains_key(&guild_id) {
+        let _ = invoker.say(ctx, "Nothing playing").await;
+        return;
+    }
+
+    let result = match component.data.custom_id.as_str() {
+        PAUSE_BUTTON => toggle_pause(ctx, invoker).await,
+        SKIP_BUTTON => skip_track(ctx, invoker).await,
+        STOP_BUTTON => stop_playing(ctx, invoker).await,
+        LOOP_BUTTON => cycle_repeat_mode(ctx, invoker).await,
+        SHUFFLE_BUTTON => shuffle_tracks(ctx, invoker).await,
+        VOLUME_DOWN_BUTTON => change_volume(ctx, invoker, -VOLUME_STEP).await,
+        VOLUME_UP_BUTTON => change_volume(ctx, invoker, VOLUME_STEP).await,
+        GRAB_BUTTON => grab_track(ctx, invoker).await,
+        _ => Ok(()),
+    };
+
+    if let Err(e) = result {
+        warn!("Error with button {}, {:?}", component.data.custom_id, e);
+        let _ = invoker.say(ctx, format!("Command returned an error, {e:?}, please report this on the support server https://discord.gg/5YytF9fPHr")).await;
+    }
+
+    let queue = queue_container.get(&guild_id).map(|queue| queue.clone());
+
+    if let Some(queue) = queue {
+        if let Err(e) = refresh_panel(&ctx.http, &queue).await {
+            warn!("Could not refresh the now playing panel, {:?}", e);
+        }
+    }
+}
@@ -1,13 +1,27 @@
-use std::fmt::Formatter;
+use std::{
+    fmt::Formatter,
+    time::{Duration, Instant},
+};
 
-use serde::Deserialize;
+use serde::{de::DeserializeOwned, Deserialize};
 
 use anyhow::anyhow;
+use serenity::prelude::Mutex;
 use tokio::process::Command;
 
 #[derive(Debug, Deserialize)]
 pub struct YtPlayListResponse {
     pub title: String,
+    pub url: Option<String>,
+    pub duration: Option<f64>,
+    pub uploader: Option<String>,
+    #[serde(default)]
+    pub thumbnails: Vec<Thumbnail>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Thumbnail {
+    pub url: String,
 }
 
 #[derive(Debug)]
@@ -28,11 +42,45 @@ impl std::fmt::Display for YtPlayListError {
 
 impl std::error::Error for YtPlayListError {}
 
+/// A music link to something the service does not let the bot read, the message says what is supported.
+#[derive(Debug)]
+pub struct UnsupportedLink(pub &'static str);
+
+impl std::fmt::Display for UnsupportedLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for UnsupportedLink {}
+
 pub async fn get_list_of_urls(url: &str) -> anyhow::Result<Vec<YtPlayListResponse>> {
-    let output = Command::new("yt-dlp")
-        .args(["-j", "--flat-playlist", url])
-        .output()
-        .await?;
+    get_flat_playlist(&["-j", "--flat-playlist", url]).await
+}
+
+/// The videos of the youtube mix for a video, the video itself comes first.
+pub async fn get_related_tracks(
+    video_id: &str,
+    limit: usize,
+) -> anyhow::Result<Vec<YtPlayListResponse>> {
+    get_flat_playlist(&[
+        "-j",
+        "--flat-playlist",
+        "--yes-playlist",
+        "--playlist-end",
+        &limit.to_string(),
+        &format!("https://www.youtube.com/watch?v={video_id}&list=RD{video_id}"),
+    ])
+    .await
+}
+
+/// The first `count` youtube results for the query.
+pub async fn search_youtube(query: &str, count: usize) -> anyhow::Result<Vec<YtPlayListResponse>> {
+    get_flat_playlist(&["-j", "--flat-playlist", &format!("ytsearch{count}:{query}")]).await
+}
+
+async fn get_flat_playlist(args: &[&str]) -> anyhow::Result<Vec<YtPlayListResponse>> {
+    let output = Command::new("yt-dlp").args(args).output().await?;
 
     if !output.status.success() {
         Err(YtPlayListError::ListOfUrlsError(output.stderr).into())
@@ -48,21 +96,84 @@ pub async fn get_list_of_urls(url: &str) -> anyhow::Result<Vec<YtPlayListRespons
     }
 }
 
+/// Reads the `t` parameter of a youtube link, e.g. `?t=90`, `&t=1m30s` or `&t=1h2m3s`.
+pub fn get_start_time_from_url(url: &str) -> Option<Duration> {
+    if !(url.contains("youtube.com/") || url.contains("youtu.be/")) {
+        return None;
+    }
+
+    let (_, query) = url.split_once('?')?;
+    let value = query
+        .split(|c| c == '&' || c == '#')
+        .find_map(|pair| pair.strip_prefix("t="))?;
+
+    let mut seconds = 0;
+    let mut number = String::new();
+
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+        } else {
+            let amount: u64 = number.parse().ok()?;
+            number.clear();
+
+            seconds += match c {
+                'h' => amount * 60 * 60,
+                'm' => amount * 60,
+                's' => amount,
+                _ => return None,
+            };
+        }
+    }
+
+    if !number.is_empty() {
+        seconds += number.parse::<u64>().ok()?;
+    }
+
+    Some(Duration::from_secs(seconds))
+}
+
+/// Reads the id of the video out of a youtube link, e.g. `watch?v=<id>` or `youtu.be/<id>`.
+pub fn get_youtube_video_id(url: &str) -> Option<&str> {
+    let id = if let Some((_, path)) = url.split_once("youtu.be/") {
+        path
+    } else if url.contains("youtube.com/") {
+        let (_, query) = url.split_once('?')?;
+        query.split('&').find_map(|pair| pair.strip_prefix("v="))?
+    } else {
+        return None;
+    };
+
+    let id = id.split(|c| c == '?' || c == '&' || c == '#').next()?;
+
+    if id.is_empty() {
+        None
+    } else {
+        Some(id)
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct YtdlMetadata {
     pub title: String,
     pub uploader: String,
-    pub duration: f32,
+    /// Live streams have no length
+    pub duration: Option<f32>,
     pub webpage_url: String,
+    pub thumbnail: Option<String>,
+    pub is_live: Option<bool>,
 }
 
+/// Links are looked up directly, anything else is searched for on youtube.
 pub async fn get_ytdl_metadata(search: &str) -> anyhow::Result<YtdlMetadata> {
+    let target = if search.starts_with("http") {
+        search.to_string()
+    } else {
+        format!("ytsearch:{search}")
+    };
+
     let output = Command::new("yt-dlp")
-        .args([
-            "--skip-download",
-            "--print-json",
-            &format!("ytsearch:{search}"),
-        ])
+        .args(["--skip-download", "--print-json", &target])
         .output()
         .await?;
 
@@ -78,39 +189,77 @@ pub async fn get_ytdl_metadata(search: &str) -> anyhow::Result<YtdlMetadata> {
     }
 }
 
+lazy_static::lazy_static! {
+    static ref SPOTIFY_TOKEN: Mutex<Option<SpotifyToken>> = Default::default();
+}
+
+/// Tokens are refreshed this long before spotify says they expire.
+const TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
 #[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct AccessToken {
+struct AccessTokenResponse {
     access_token: String,
+    expires_in: u64,
 }
 
-async fn get_spotify_access_token(client: reqwest::Client) -> anyhow::Result<AccessToken> {
-    let res = client
-        .get("https://open.spotify.com/get_access_token")
-        .header("User-Agent", "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/78.0.3904.108 Safari/537.36")
+#[derive(Debug, Clone)]
+struct SpotifyToken {
+    access_token: String,
+    expires_at: Instant,
+}
+
+/// Uses the client credentials flow with `SPOTIFY_CLIENT_ID` and `SPOTIFY_CLIENT_SECRET`,
+/// the token is cached until it is about to expire.
+async fn get_spotify_access_token(client: &reqwest::Client) -> anyhow::Result<String> {
+    let mut cached = SPOTIFY_TOKEN.lock().await;
+
+    if let Some(token) = cached.as_ref() {
+        if token.expires_at > Instant::now() + TOKEN_EXPIRY_MARGIN {
+            return Ok(token.access_token.clone());
+        }
+    }
+
+    let client_id = std::env::var("SPOTIFY_CLIENT_ID")
+        .map_err(|_| anyhow!("Expected SPOTIFY_CLIENT_ID in dotenv file"))?;
+    let client_secret = std::env::var("SPOTIFY_CLIENT_SECRET")
+        .map_err(|_| anyhow!("Expected SPOTIFY_CLIENT_SECRET in dotenv file"))?;
+
+    let res: AccessTokenResponse = client
+        .post("https://accounts.spotify.com/api/token")
+        .basic_auth(client_id, Some(client_secret))
+        .form(&[("grant_type", "client_credentials")])
         .send()
+        .await?
+        .error_for_status()?
+        .json()
         .await?;
 
-    let token: AccessToken = res.json().await?;
+    let token = SpotifyToken {
+        access_token: res.access_token,
+        expires_at: Instant::now() + Duration::from_secs(res.expires_in),
+    };
 
-    tracing::error!("{:?}", token);
+    *cached = Some(token.clone());
 
-    Ok(token)
+    Ok(token.access_token)
 }
 
 #[derive(Debug, Deserialize)]
-pub struct SpotifyPlaylistResponse {
-    pub items: Vec<Items>,
+struct SpotifyPage<T> {
+    items: Vec<T>,
+    next: Option<String>,
 }
 
+/// Unavailable songs in a playlist have no track.
 #[derive(Debug, Deserialize)]
-pub struct Items {
-    pub track: Track,
+struct PlaylistItem {
+    track: Option<Track>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct Track {
     pub name: String,
+    #[serde(default)]
     pub artists: Vec<Artists>,
 }
 
@@ -119,41 +268,672 @@ pub struct Artists {
     pub name: String,
 }
 
+impl Track {
+    /// What to search youtube for to find the song.
+    pub fn search_query(&self) -> String {
+        match self.artists.first() {
+            Some(artist) => format!("{} {}", self.name, artist.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SpotifyTopTracksResponse {
+    tracks: Vec<Track>,
+}
+
+/// Reads spotify links like `https://open.spotify.com/album/<id>?si=...`,
+/// including localised ones like `open.spotify.com/intl-de/track/<id>`, and uris like `spotify:track:<id>`.
+fn parse_spotify_link(url: &str) -> Option<MusicLink<'_>> {
+    let (kind, id) = if let Some(uri) = url.strip_prefix("spotify:") {
+        uri.split_once(':')?
+    } else {
+        let (_, path) = url.split_once("open.spotify.com/")?;
+        let path = match path.split_once('/') {
+            Some((locale, rest)) if locale.starts_with("intl-") => rest,
+            _ => path,
+        };
+
+        path.split_once('/')?
+    };
+
+    let kind = match kind {
+        "playlist" => LinkKind::Playlist,
+        "album" => LinkKind::Album,
+        "track" => LinkKind::Song,
+        "artist" => LinkKind::Artist,
+        _ => return None,
+    };
+
+    MusicLink::new(MusicService::Spotify, kind, strip_link_suffix(id))
+}
+
+async fn get_spotify<T: DeserializeOwned>(
+    client: &reqwest::Client,
+    token: &str,
+    url: &str,
+) -> anyhow::Result<T> {
+    let res = client
+        .get(url)
+        .bearer_auth(token)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(res.json().await?)
+}
+
+/// Follows the `next` links until every page has been fetched.
+async fn get_all_spotify_pages<T: DeserializeOwned>(
+    client: &reqwest::Client,
+    token: &str,
+    url: String,
+) -> anyhow::Result<Vec<T>> {
+    let mut items = Vec::new();
+    let mut next = Some(url);
+
+    while let Some(url) = next {
+        let page: SpotifyPage<T> = get_spotify(client, token, &url).await?;
+
+        items.extend(page.items);
+        next = page.next;
+    }
+
+    Ok(items)
+}
+
+/// The songs behind a spotify link, for artists these are their top songs.
+async fn get_spotify_tracks(
+    client: &reqwest::Client,
+    kind: LinkKind,
+    id: &str,
+) -> anyhow::Result<Vec<Track>> {
+    if kind == LinkKind::Playlist {
+        return get_list_of_spotify_tracks(client.clone(), id).await;
+    }
+
+    let token = get_spotify_access_token(client).await?;
+
+    match kind {
+        LinkKind::Album => {
+            get_all_spotify_pages(
+                client,
+                &token,
+                format!("https://api.spotify.com/v1/albums/{id}/tracks?limit=50"),
+            )
+            .await
+        }
+        LinkKind::Artist => {
+            let url = format!("https://api.spotify.com/v1/artists/{id}/top-tracks?market=US");
+            let res: SpotifyTopTracksResponse = get_spotify(client, &token, &url).await?;
+
+            Ok(res.tracks)
+        }
+        _ => {
+            let url = format!("https://api.spotify.com/v1/tracks/{id}");
+
+            Ok(vec![get_spotify(client, &token, &url).await?])
+        }
+    }
+}
+
+/// Every available song of the playlist, unavailable ones are left out.
 pub async fn get_list_of_spotify_tracks(
     client: reqwest::Client,
     playlist_id: &str,
-) -> anyhow::Result<SpotifyPlaylistResponse> {
-    match get_spotify_access_token(client.clone()).await {
-        Ok(token) => {
-            let res = client
-                .get(&format!(
-                    "https://api.spotify.com/v1/playlists/{playlist_id}/tracks"
-                ))
-                .bearer_auth(token.access_token)
+) -> anyhow::Result<Vec<Track>> {
+    let token = get_spotify_access_token(&client).await?;
+
+    let items: Vec<PlaylistItem> = get_all_spotify_pages(
+        &client,
+        &token,
+        format!("https://api.spotify.com/v1/playlists/{playlist_id}/tracks?limit=100"),
+    )
+    .await?;
+
+    Ok(items.into_iter().filter_map(|item| item.track).collect())
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MusicService {
+    Spotify,
+    AppleMusic,
+    Deezer,
+    Tidal,
+    SoundCloud,
+}
+
+impl MusicService {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Spotify => "spotify",
+            Self::AppleMusic => "apple music",
+            Self::Deezer => "deezer",
+            Self::Tidal => "tidal",
+            Self::SoundCloud => "soundcloud",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LinkKind {
+    Song,
+    Album,
+    Playlist,
+    Artist,
+}
+
+impl LinkKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Song => "song",
+            Self::Album => "album",
+            Self::Playlist => "playlist",
+            Self::Artist => "artist",
+        }
+    }
+}
+
+/// A link to a music service that is not played directly by yt-dlp.
+/// For soundcloud the id is the whole link, since yt-dlp plays those directly.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct MusicLink<'a> {
+    pub service: MusicService,
+    pub kind: LinkKind,
+    pub id: &'a str,
+}
+
+impl<'a> MusicLink<'a> {
+    fn new(service: MusicService, kind: LinkKind, id: &'a str) -> Option<Self> {
+        if id.is_empty() {
+            None
+        } else {
+            Some(Self { service, kind, id })
+        }
+    }
+
+    /// e.g. `deezer album`
+    pub fn description(&self) -> String {
+        format!("{} {}", self.service.name(), self.kind.name())
+    }
+}
+
+/// A song found through a music link, `name` is what gets searched for or played.
+#[derive(Debug)]
+pub struct ResolvedTrack {
+    pub name: String,
+    pub title: Option<String>,
+    pub duration: Option<Duration>,
+}
+
+impl From<Track> for ResolvedTrack {
+    fn from(track: Track) -> Self {
+        Self {
+            name: track.search_query(),
+            title: None,
+            duration: None,
+        }
+    }
+}
+
+/// Cuts off the query, fragment or trailing slash of the last part of a link.
+fn strip_link_suffix(id: &str) -> &str {
+    id.split(|c| c == '?' || c == '#' || c == '/')
+        .next()
+        .unwrap_or_default()
+}
+
+/// The parts of the path of a link, without the query.
+fn link_path_segments<'a>(url: &'a str, host: &str) -> Option<Vec<&'a str>> {
+    let (_, path) = url.split_once(host)?;
+    let path = path.split(|c| c == '?' || c == '#').next()?;
+
+    Some(path.split('/').filter(|part| !part.is_empty()).collect())
+}
+
+/// Reads links like `music.apple.com/us/album/<name>/<id>?i=<song id>` or `music.apple.com/us/song/<name>/<id>`.
+fn parse_apple_music_link(url: &str) -> Option<MusicLink<'_>> {
+    let segments = link_path_segments(url, "music.apple.com/")?;
+
+    let kind = match *segments.get(1)? {
+        "album" => LinkKind::Album,
+        "song" => LinkKind::Song,
+        "playlist" => LinkKind::Playlist,
+        "artist" => LinkKind::Artist,
+        _ => return None,
+    };
+
+    let song_id = url
+        .split_once('?')
+        .and_then(|(_, query)| query.split('&').find_map(|pair| pair.strip_prefix("i=")))
+        .map(strip_link_suffix);
+
+    match song_id {
+        Some(song_id) if kind == LinkKind::Album => {
+            MusicLink::new(MusicService::AppleMusic, LinkKind::Song, song_id)
+        }
+        _ => MusicLink::new(MusicService::AppleMusic, kind, *segments.last()?),
+    }
+}
+
+/// Reads links like `www.deezer.com/en/track/<id>`, the language is optional.
+fn parse_deezer_link(url: &str) -> Option<MusicLink<'_>> {
+    let segments = link_path_segments(url, "deezer.com/")?;
+
+    let (kind, id) = match segments.as_slice() {
+        [_, kind, id] | [kind, id] => (*kind, *id),
+        _ => return None,
+    };
+
+    let kind = match kind {
+        "track" => LinkKind::Song,
+        "album" => LinkKind::Album,
+        "playlist" => LinkKind::Playlist,
+        "artist" => LinkKind::Artist,
+        _ => return None,
+    };
+
+    MusicLink::new(MusicService::Deezer, kind, id)
+}
+
+/// Reads links like `tidal.com/browse/track/<id>` or `listen.tidal.com/album/<id>`.
+fn parse_tidal_link(url: &str) -> Option<MusicLink<'_>> {
+    let segments = link_path_segments(url, "tidal.com/")?;
+
+    segments.windows(2).find_map(|pair| {
+        let kind = match pair[0] {
+            "track" => LinkKind::Song,
+            "album" => LinkKind::Album,
+            "playlist" => LinkKind::Playlist,
+            "artist" => LinkKind::Artist,
+            _ => return None,
+        };
+
+        MusicLink::new(MusicService::Tidal, kind, pair[1])
+    })
+}
+
+/// Reads links like `soundcloud.com/<user>/<song>` or `soundcloud.com/<user>/sets/<playlist>`.
+fn parse_soundcloud_link(url: &str) -> Option<MusicLink<'_>> {
+    let segments = link_path_segments(url, "soundcloud.com/")?;
+
+    let kind = match segments.as_slice() {
+        [_, "sets", _] => LinkKind::Playlist,
+        [_, "tracks" | "albums" | "sets" | "popular-tracks"] => LinkKind::Artist,
+        [_, _] => LinkKind::Song,
+        _ => return None,
+    };
+
+    MusicLink::new(MusicService::SoundCloud, kind, url)
+}
+
+/// Recognises links to the music services that need to be resolved before they can be played.
+pub fn parse_music_link(url: &str) -> Option<MusicLink<'_>> {
+    let parsers: [fn(&str) -> Option<MusicLink<'_>>; 5] = [
+        parse_spotify_link,
+        parse_apple_music_link,
+        parse_deezer_link,
+        parse_tidal_link,
+        parse_soundcloud_link,
+    ];
+
+    parsers.iter().find_map(|parse| parse(url))
+}
+
+/// Finds the songs behind a music link, which are then searched for on youtube,
+/// or for soundcloud played directly.
+pub async fn resolve_music_link(
+    client: &reqwest::Client,
+    link: MusicLink<'_>,
+) -> anyhow::Result<Vec<ResolvedTrack>> {
+    match link.service {
+        MusicService::Spotify => Ok(get_spotify_tracks(client, link.kind, link.id)
+            .await?
+            .into_iter()
+            .map(ResolvedTrack::from)
+            .collect()),
+        MusicService::AppleMusic => get_apple_music_tracks(client, link.kind, link.id).await,
+        MusicService::Deezer => get_deezer_tracks(client, link.kind, link.id).await,
+        MusicService::Tidal => get_tidal_tracks(client, link.kind, link.id).await,
+        MusicService::SoundCloud => get_soundcloud_tracks(link.kind, link.id).await,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ItunesLookupResponse {
+    results: Vec<ItunesResult>,
+}
+
+/// Lookups also return the album or artist itself, only the songs have a `trackName`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ItunesResult {
+    track_name: Option<String>,
+    artist_name: Option<String>,
+    track_time_millis: Option<u64>,
+}
+
+/// Uses the public itunes lookup, apple music playlists can not be read without a developer token.
+async fn get_apple_music_tracks(
+    client: &reqwest::Client,
+    kind: LinkKind,
+    id: &str,
+) -> anyhow::Result<Vec<ResolvedTrack>> {
+    let query = match kind {
+        LinkKind::Song => vec![("id", id)],
+        LinkKind::Album => vec![("id", id), ("entity", "song")],
+        LinkKind::Artist => vec![("id", id), ("entity", "song"), ("limit", "10")],
+        LinkKind::Playlist => {
+            return Err(UnsupportedLink(
+                "Apple music playlists are not supported, only songs, albums and artists",
+            )
+            .into())
+        }
+    };
+
+    let res: ItunesLookupResponse = client
+        .get("https://itunes.apple.com/lookup")
+        .query(&query)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(res
+        .results
+        .into_iter()
+        .filter_map(|result| {
+            let title = result.track_name?;
+
+            Some(ResolvedTrack {
+                name: match &result.artist_name {
+                    Some(artist) => format!("{title} {artist}"),
+                    None => title.clone(),
+                },
+                title: Some(title),
+                duration: result.track_time_millis.map(Duration::from_millis),
+            })
+        })
+        .collect())
+}
+
+#[derive(Debug, Deserialize)]
+struct DeezerPage {
+    data: Vec<DeezerTrack>,
+    next: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeezerTrack {
+    title: String,
+    duration: Option<u64>,
+    artist: Artists,
+}
+
+impl From<DeezerTrack> for ResolvedTrack {
+    fn from(track: DeezerTrack) -> Self {
+        Self {
+            name: format!("{} {}", track.title, track.artist.name),
+            title: Some(track.title),
+            duration: track.duration.map(Duration::from_secs),
+        }
+    }
+}
+
+async fn get_deezer_tracks(
+    client: &reqwest::Client,
+    kind: LinkKind,
+    id: &str,
+) -> anyhow::Result<Vec<ResolvedTrack>> {
+    let mut next = Some(match kind {
+        LinkKind::Song => {
+            let track: DeezerTrack = client
+                .get(&format!("https://api.deezer.com/track/{id}"))
                 .send()
+                .await?
+                .error_for_status()?
+                .json()
                 .await?;
 
-            let playlist: SpotifyPlaylistResponse = res.json().await?;
-
-            Ok(playlist)
+            return Ok(vec![track.into()]);
         }
-        Err(e) => Err(e),
+        LinkKind::Album => format!("https://api.deezer.com/album/{id}/tracks"),
+        LinkKind::Playlist => format!("https://api.deezer.com/playlist/{id}/tracks"),
+        LinkKind::Artist => format!("https://api.deezer.com/artist/{id}/top?limit=10"),
+    });
+
+    let mut tracks = Vec::new();
+
+    while let Some(url) = next {
+        let page: DeezerPage = client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        tracks.extend(page.data.into_iter().map(ResolvedTrack::from));
+        next = page.next;
+    }
+
+    Ok(tracks)
+}
+
+/// Reads the `og:title` of the song's page, tidal has no public api for albums or playlists.
+async fn get_tidal_tracks(
+    client: &reqwest::Client,
+    kind: LinkKind,
+    id: &str,
+) -> anyhow::Result<Vec<ResolvedTrack>> {
+    if kind != LinkKind::Song {
+        return Err(UnsupportedLink(
+            "Only tidal songs are supported, not albums, playlists or artists",
+        )
+        .into());
     }
+
+    let page = client
+        .get(&format!("https://tidal.com/browse/track/{id}"))
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    let title = page
+        .split_once(r#"property="og:title" content=""#)
+        .and_then(|(_, rest)| rest.split_once('"'))
+        .map(|(title, _)| title.to_string())
+        .ok_or_else(|| anyhow!("Could not find the title of tidal song {id}"))?;
+
+    Ok(vec![ResolvedTrack {
+        name: title.clone(),
+        title: Some(title),
+        duration: None,
+    }])
+}
+
+/// yt-dlp can play soundcloud songs directly, sets and users are split into their songs.
+async fn get_soundcloud_tracks(kind: LinkKind, url: &str) -> anyhow::Result<Vec<ResolvedTrack>> {
+    if kind == LinkKind::Song {
+        return Ok(vec![ResolvedTrack {
+            name: url.to_string(),
+            title: None,
+            duration: None,
+        }]);
+    }
+
+    Ok(get_list_of_urls(url)
+        .await?
+        .into_iter()
+        .filter_map(|entry| {
+            Some(ResolvedTrack {
+                name: entry.url?,
+                title: Some(entry.title),
+                duration: entry.duration.map(Duration::from_secs_f64),
+            })
+        })
+        .collect())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{get_list_of_spotify_tracks, get_spotify_access_token};
+    use std::time::Duration;
+
+    use super::{
+        get_list_of_spotify_tracks, get_spotify_access_token, get_start_time_from_url,
+        get_youtube_video_id, parse_music_link, LinkKind, MusicLink, MusicService,
+    };
+
+    #[test]
+    fn test_start_time_from_url() {
+        assert_eq!(
+            get_start_time_from_url("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=90"),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(
+            get_start_time_from_url("https://youtu.be/dQw4w9WgXcQ?t=1m30s"),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(
+            get_start_time_from_url("https://www.youtube.com/watch?t=1h2m3s&v=dQw4w9WgXcQ"),
+            Some(Duration::from_secs(3723))
+        );
+        assert_eq!(
+            get_start_time_from_url("https://www.youtube.com/watch?v=dQw4w9WgXcQ"),
+            None
+        );
+        assert_eq!(get_start_time_from_url("https://example.com/?t=90"), None);
+    }
+
+    #[test]
+    fn test_youtube_video_id() {
+        assert_eq!(
+            get_youtube_video_id("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=90"),
+            Some("dQw4w9WgXcQ")
+        );
+        assert_eq!(
+            get_youtube_video_id("https://youtu.be/dQw4w9WgXcQ?t=1m30s"),
+            Some("dQw4w9WgXcQ")
+        );
+        assert_eq!(
+            get_youtube_video_id("https://music.youtube.com/watch?list=RDAMVM&v=dQw4w9WgXcQ"),
+            Some("dQw4w9WgXcQ")
+        );
+        assert_eq!(
+            get_youtube_video_id("https://www.youtube.com/playlist?list=PL123"),
+            None
+        );
+        assert_eq!(get_youtube_video_id("https://example.com/?v=90"), None);
+    }
+
+    fn link(service: MusicService, kind: LinkKind, id: &str) -> Option<MusicLink<'_>> {
+        Some(MusicLink { service, kind, id })
+    }
+
+    #[test]
+    fn test_parse_spotify_link() {
+        assert_eq!(
+            parse_music_link("https://open.spotify.com/track/4cOdK2wGLETKBW3PvgPWqT?si=abc"),
+            link(
+                MusicService::Spotify,
+                LinkKind::Song,
+                "4cOdK2wGLETKBW3PvgPWqT"
+            )
+        );
+        assert_eq!(
+            parse_music_link("https://open.spotify.com/intl-de/album/1ATL5GLyefJaxhQzSPVrLX"),
+            link(
+                MusicService::Spotify,
+                LinkKind::Album,
+                "1ATL5GLyefJaxhQzSPVrLX"
+            )
+        );
+        assert_eq!(
+            parse_music_link("https://open.spotify.com/artist/0gxyHStUsqpMadRV0Di1Qt"),
+            link(
+                MusicService::Spotify,
+                LinkKind::Artist,
+                "0gxyHStUsqpMadRV0Di1Qt"
+            )
+        );
+        assert_eq!(
+            parse_music_link("spotify:playlist:5I1uPiJpPmphKfQHDjWHFa"),
+            link(
+                MusicService::Spotify,
+                LinkKind::Playlist,
+                "5I1uPiJpPmphKfQHDjWHFa"
+            )
+        );
+        assert_eq!(
+            parse_music_link("https://open.spotify.com/show/5CfCWKI5pZ28U0uOzXkDHe"),
+            None
+        );
+        assert_eq!(
+            parse_music_link("https://www.youtube.com/watch?v=dQw4w9WgXcQ"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_music_link() {
+        assert_eq!(
+            parse_music_link("https://music.apple.com/us/album/thriller/269572838?i=269573364"),
+            link(MusicService::AppleMusic, LinkKind::Song, "269573364")
+        );
+        assert_eq!(
+            parse_music_link("https://music.apple.com/us/album/thriller/269572838"),
+            link(MusicService::AppleMusic, LinkKind::Album, "269572838")
+        );
+        assert_eq!(
+            parse_music_link("https://www.deezer.com/en/track/3135556"),
+            link(MusicService::Deezer, LinkKind::Song, "3135556")
+        );
+        assert_eq!(
+            parse_music_link("https://www.deezer.com/playlist/908622995?utm_source=x"),
+            link(MusicService::Deezer, LinkKind::Playlist, "908622995")
+        );
+        assert_eq!(
+            parse_music_link("https://tidal.com/browse/track/77646170"),
+            link(MusicService::Tidal, LinkKind::Song, "77646170")
+        );
+        assert_eq!(
+            parse_music_link("https://soundcloud.com/artist/some-song"),
+            link(
+                MusicService::SoundCloud,
+                LinkKind::Song,
+                "https://soundcloud.com/artist/some-song"
+            )
+        );
+        assert_eq!(
+            parse_music_link("https://soundcloud.com/artist/sets/some-set"),
+            link(
+                MusicService::SoundCloud,
+                LinkKind::Playlist,
+                "https://soundcloud.com/artist/sets/some-set"
+            )
+        );
+        assert_eq!(parse_music_link("https://www.deezer.com/en/"), None);
+    }
 
     #[tokio::test]
     async fn test_spotify_access_token() {
+        dotenv::dotenv().ok();
+
         let client = reqwest::Client::new();
-        let token = get_spotify_access_token(client).await.unwrap();
-        println!("{token:?}");
+        let token = get_spotify_access_token(&client).await.unwrap();
+        let cached = get_spotify_access_token(&client).await.unwrap();
+
+        assert_eq!(token, cached);
     }
 
     #[tokio::test]
     async fn test_spotify_playlist_tracks() {
+        dotenv::dotenv().ok();
+
         let client = reqwest::Client::new();
         let res = get_list_of_spotify_tracks(client, "5I1uPiJpPmphKfQHDjWHFa")
             .await
@@ -1,4 +1,11 @@
-use std::{collections::VecDeque, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    convert::TryInto,
+    fmt,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::{anyhow, Result};
 use dashmap::DashMap;
@@ -7,23 +14,72 @@ use serenity::{
     async_trait,
     client::Context,
     http::Http,
-    model::id::{ChannelId, GuildId},
+    model::id::{ChannelId, GuildId, MessageId, UserId},
     prelude::{Mutex as AsyncMutex, TypeMapKey},
 };
 use songbird::{
-    input::{Input, Restartable},
-    tracks::{create_player_with_uuid, TrackHandle},
+    input::{Input, Metadata, Restartable},
+    tracks::{create_player_with_uuid, LoopState, PlayMode, Track, TrackHandle},
     Call, Event, EventContext, EventHandler, TrackEvent,
 };
 use tracing::{info, warn};
 use uuid::Uuid;
 
-use crate::voice_events::TrackStartNotifier;
+use crate::{
+    autoplay::find_autoplay_track,
+    fades::{fade_out_and_stop, CrossfadeWatcher, CROSSFADE_CHECK_INTERVAL},
+    filters::{filter_chain, speed_factor, AudioFilter, FilteredSource},
+    library::{get_library_path, is_audio_attachment},
+    now_playing_panel::PANEL_UPDATE_INTERVAL,
+    radio::RadioSource,
+    voice_events::TrackStartNotifier,
+};
 
 #[derive(Debug, Clone)]
 pub struct QueuedTrack {
     pub name: String,
     pub uuid: Uuid,
+    /// The user who added the track, unknown for queues restored after a restart
+    pub requester: Option<UserId>,
+    pub start_time: Option<Duration>,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub duration: Option<Duration>,
+    pub uploader: Option<String>,
+    pub thumbnail: Option<String>,
+    /// Live streams and radio stations have no length
+    pub live: bool,
+    /// Radio stations are streamed directly instead of through yt-dlp
+    pub radio: bool,
+    /// What a radio station says it is currently playing
+    pub stream_title: Option<String>,
+    /// How much faster than normal the filters the track was loaded with make it play
+    pub speed: f64,
+}
+
+impl QueuedTrack {
+    pub fn new(name: String, requester: Option<UserId>) -> Self {
+        Self {
+            name,
+            uuid: Uuid::new_v4(),
+            requester,
+            start_time: None,
+            title: None,
+            url: None,
+            duration: None,
+            uploader: None,
+            thumbnail: None,
+            live: false,
+            radio: false,
+            stream_title: None,
+            speed: 1.0,
+        }
+    }
+
+    /// The title of the track if it is known, otherwise what was searched for.
+    pub fn display_title(&self) -> &str {
+        self.title.as_deref().unwrap_or(&self.name)
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -36,6 +92,101 @@ pub struct QueueCore {
     tracks: VecDeque<QueuedTrack>,
     current_track: Arc<Mutex<Option<TrackHandle>>>,
     next_track: Mutex<Option<TrackHandle>>,
+    text_channel: Option<ChannelId>,
+    panel_message: Option<MessageId>,
+    repeat_mode: RepeatMode,
+    /// Repeating a set amount of times was chosen while nothing played, so it goes to the next track that loads
+    repeat_times_pending: bool,
+    /// Upcoming tracks take turns between requesters instead of playing in the order they were added
+    fair_queue: bool,
+    /// Related tracks are added once the queue runs dry
+    autoplay: bool,
+    skip_votes: HashSet<UserId>,
+    /// Recently played tracks, the newest first
+    history: VecDeque<QueuedTrack>,
+    /// How many of the newest history entries are not in the database yet
+    unsaved_history: usize,
+    history_loaded: bool,
+    changed: bool,
+    /// Applied to every track as it is loaded
+    filters: Vec<AudioFilter>,
+    /// Evens out the loudness of tracks as they are loaded
+    normalise: bool,
+    /// How long the end of a track overlaps with the start of the next one, zero when off
+    crossfade: Duration,
+}
+
+pub const HISTORY_LENGTH: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepeatMode {
+    Off,
+    Track,
+    Queue,
+    TrackTimes(usize),
+}
+
+impl Default for RepeatMode {
+    fn default() -> Self {
+        Self::Off
+    }
+}
+
+impl RepeatMode {
+    /// The mode the repeat button on the now playing message switches to.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Off => Self::Track,
+            Self::Track => Self::Queue,
+            Self::Queue | Self::TrackTimes(_) => Self::Off,
+        }
+    }
+
+    pub fn from_db(mode: i16, times: i32) -> Self {
+        match mode {
+            1 => Self::Track,
+            2 => Self::Queue,
+            3 => Self::TrackTimes(times.try_into().unwrap_or_default()),
+            _ => Self::Off,
+        }
+    }
+
+    pub fn to_db(self) -> (i16, i32) {
+        match self {
+            Self::Off => (0, 0),
+            Self::Track => (1, 0),
+            Self::Queue => (2, 0),
+            Self::TrackTimes(times) => (3, times.try_into().unwrap_or(i32::MAX)),
+        }
+    }
+}
+
+impl fmt::Display for RepeatMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Off => write!(f, "Off"),
+            Self::Track => write!(f, "Track"),
+            Self::Queue => write!(f, "Queue"),
+            Self::TrackTimes(times) => write!(f, "Track ({times} times)"),
+        }
+    }
+}
+
+impl FromStr for RepeatMode {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_ref() {
+            "off" | "none" => Ok(Self::Off),
+            "track" | "song" | "one" => Ok(Self::Track),
+            "queue" | "all" => Ok(Self::Queue),
+            times => match times.parse() {
+                Ok(0) => Ok(Self::Off),
+                Ok(times) => Ok(Self::TrackTimes(times)),
+                Err(_) => Err("Must be off, track, queue or a number of times to repeat"),
+            },
+        }
+    }
 }
 struct PlayNextTrack {
     remote_lock: Arc<Mutex<QueueCore>>,
@@ -47,78 +198,347 @@ struct PlayNextTrack {
 #[async_trait]
 impl EventHandler for PlayNextTrack {
     async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
-        {
-            let mut inner = self.remote_lock.lock();
+        let ended_uuid = match ctx {
+            EventContext::Track(ts) => ts.first().map(|handle| handle.1.uuid())?,
+            _ => return None,
+        };
+
+        let queue = Queue {
+            inner: self.remote_lock.clone(),
+        };
 
-            let front_ended = match ctx {
-                EventContext::Track(ts) => {
-                    let queue_uuid = inner.tracks.front().map(|input| input.uuid);
-                    let ended_uuid = ts.first().map(|handle| handle.1.uuid());
+        queue
+            .play_next(
+                ended_uuid,
+                self.driver.clone(),
+                self.chan_id,
+                self.http.clone(),
+            )
+            .await;
 
-                    queue_uuid.is_some() && queue_uuid == ended_uuid
-                }
-                _ => false,
-            };
+        None
+    }
+}
+
+/// Library files, attachments and radio stations are streamed with ffmpeg, everything else goes through yt-dlp.
+/// Tracks with filters or normalisation always go through ffmpeg, searches are looked up with yt-dlp first.
+async fn get_input_from_queued_track(track: QueuedTrack, queue: &Queue) -> Result<Input> {
+    let filters = queue.filters();
+    let chain = filter_chain(&filters, queue.normalise());
+    let speed = speed_factor(&filters);
+
+    if track.radio {
+        let source = RadioSource::new(track.name, chain, queue.clone(), track.uuid);
+
+        return match Restartable::new(source, false).await {
+            Ok(r) => Ok(r.into()),
+            Err(e) => Err(anyhow!("{:?}", e)),
+        };
+    }
+
+    let library_path = get_library_path(&track.name);
+    let direct = is_audio_attachment(&track.name);
 
-            if !front_ended {
-                return None;
+    let restartable = match (chain, library_path) {
+        (Some(chain), Some(path)) => {
+            Restartable::new(FilteredSource::direct(path, chain, speed), false).await
+        }
+        (Some(chain), None) if direct => {
+            Restartable::new(FilteredSource::direct(track.name, chain, speed), false).await
+        }
+        (Some(chain), None) => {
+            Restartable::new(FilteredSource::ytdl(&track.name, chain, speed), false).await
+        }
+        (None, Some(path)) => Restartable::ffmpeg(path, true).await,
+        (None, None) if direct => Restartable::ffmpeg(track.name, true).await,
+        (None, None) => Restartable::ytdl_search(&track.name, true).await,
+    };
+
+    match restartable {
+        Ok(r) => Ok(r.into()),
+        Err(e) => Err(anyhow!("{:?}", e)),
+    }
+}
+
+/// Loads a queued track and attaches the queue's events to it, it still has to be handed to the driver.
+async fn load_track(
+    queued_track: &QueuedTrack,
+    queue: &Queue,
+    driver: Arc<AsyncMutex<Call>>,
+    chan_id: ChannelId,
+    http: Arc<Http>,
+) -> Result<(Track, TrackHandle)> {
+    let speed = speed_factor(&queue.filters());
+    let input = get_input_from_queued_track(queued_track.clone(), queue).await?;
+
+    queue.fill_metadata(queued_track.uuid, &input.metadata, speed);
+
+    let (track, handle) = create_player_with_uuid(input, queued_track.uuid);
+
+    // The start time is in the time of the track, the position of the handle is sped up by the filters
+    if let Some(start_time) = queued_track.start_time {
+        handle.seek_time(start_time.div_f64(speed))?;
+    }
+
+    handle.add_event(
+        Event::Track(TrackEvent::End),
+        PlayNextTrack {
+            driver: driver.clone(),
+            remote_lock: queue.inner.clone(),
+            chan_id,
+            http: http.clone(),
+        },
+    )?;
+    handle.add_event(
+        Event::Delayed(Duration::from_millis(5)),
+        TrackStartNotifier {
+            chan_id,
+            http: http.clone(),
+            queue: queue.clone(),
+        },
+    )?;
+    handle.add_event(
+        Event::Periodic(PANEL_UPDATE_INTERVAL, None),
+        TrackStartNotifier {
+            chan_id,
+            http: http.clone(),
+            queue: queue.clone(),
+        },
+    )?;
+    handle.add_event(
+        Event::Periodic(CROSSFADE_CHECK_INTERVAL, None),
+        CrossfadeWatcher {
+            queue: queue.clone(),
+            driver,
+            chan_id,
+            http,
+        },
+    )?;
+
+    Ok((track, handle))
+}
+
+/// Where a track has to be inserted for requesters to take turns,
+/// it goes behind every track of the round it belongs to.
+/// The currently playing track counts towards the rounds, but nothing is inserted before it.
+fn fair_queue_index(tracks: &VecDeque<QueuedTrack>, requester: Option<UserId>) -> usize {
+    let round = tracks
+        .iter()
+        .filter(|track| track.requester == requester)
+        .count();
+    let mut rounds: HashMap<Option<UserId>, usize> = HashMap::new();
+
+    for (idx, track) in tracks.iter().enumerate() {
+        let track_round = rounds.entry(track.requester).or_default();
+
+        if idx > 0 && *track_round > round {
+            return idx;
+        }
+
+        *track_round += 1;
+    }
+
+    tracks.len()
+}
+
+impl QueueCore {
+    fn push_history(&mut self, track: QueuedTrack) {
+        self.history.push_front(track);
+        self.history.truncate(HISTORY_LENGTH);
+        self.unsaved_history = (self.unsaved_history + 1).min(self.history.len());
+    }
+    /// Stops the preloaded track when it is no longer the one that plays next,
+    /// the new next track gets loaded once the current one ends.
+    fn discard_stale_preload(&mut self) {
+        let next_uuid = self.tracks.get(1).map(|track| track.uuid);
+        let mut next_track = self.next_track.lock();
+
+        if next_track.as_ref().map(|handle| handle.uuid()) != next_uuid {
+            if let Some(handle) = next_track.take() {
+                let _ = handle.stop();
+            }
+        }
+    }
+
+    fn apply_repeat_mode(&mut self, handle: &TrackHandle) {
+        match self.repeat_mode {
+            RepeatMode::Track => {
+                let _ = handle.enable_loop();
+            }
+            RepeatMode::TrackTimes(times) if self.repeat_times_pending => {
+                self.repeat_times_pending = false;
+                let _ = handle.loop_for(times);
             }
+            // Repeating a track a set amount of times only applies to the track it was set on
+            RepeatMode::TrackTimes(_) => self.repeat_mode = RepeatMode::Off,
+            RepeatMode::Off | RepeatMode::Queue => {}
+        }
+    }
+}
+
+impl Queue {
+    /// Moves on from the track that ended to the next one.
+    /// Nothing happens when the ended track is not the current one anymore.
+    pub async fn play_next(
+        &self,
+        ended_uuid: Uuid,
+        driver: Arc<AsyncMutex<Call>>,
+        chan_id: ChannelId,
+        http: Arc<Http>,
+    ) {
+        if self.advance(ended_uuid) {
+            self.load_upcoming(driver, chan_id, http).await;
+        }
+    }
 
-            inner.tracks.pop_front();
+    /// Pops the ended track and plays the preloaded next one, without loading anything.
+    /// Returns false when the ended track is not the current one anymore.
+    pub fn advance(&self, ended_uuid: Uuid) -> bool {
+        let mut inner = self.inner.lock();
 
-            if let Some(next_track) = inner.next_track.lock().as_ref() {
+        let front_ended = inner.tracks.front().map(|track| track.uuid) == Some(ended_uuid);
+
+        if !front_ended {
+            return false;
+        }
+
+        let ended_track = inner.tracks.pop_front();
+        inner.skip_votes.clear();
+        inner.changed = true;
+
+        if let Some(ended_track) = ended_track {
+            inner.push_history(ended_track.clone());
+
+            if let RepeatMode::Queue = inner.repeat_mode {
+                inner.tracks.push_back(QueuedTrack {
+                    uuid: Uuid::new_v4(),
+                    ..ended_track
+                });
+            }
+        }
+
+        let front_uuid = inner.tracks.front().map(|track| track.uuid);
+        let next_track = inner.next_track.lock().take();
+
+        match next_track {
+            // The preloaded track is only played if it is still the next one in the queue
+            Some(next_track) if Some(next_track.uuid()) == front_uuid => {
                 let _ = next_track.play();
+                inner.apply_repeat_mode(&next_track);
                 let mut current_track = inner.current_track.lock();
-                *current_track = Some(next_track.clone())
-            } else {
+                *current_track = Some(next_track)
+            }
+            stale_track => {
+                if let Some(handle) = stale_track {
+                    let _ = handle.stop();
+                }
+
                 let mut current_track = inner.current_track.lock();
                 *current_track = None;
             }
+        }
+
+        info!("Queued track ended: {}.", ended_uuid);
+        info!("{} tracks remain.", inner.tracks.len());
+
+        true
+    }
+
+    /// Autoplays once the queue ran dry, then loads the front of the queue if nothing plays and preloads the next track.
+    pub async fn load_upcoming(
+        &self,
+        driver: Arc<AsyncMutex<Call>>,
+        chan_id: ChannelId,
+        http: Arc<Http>,
+    ) {
+        let autoplay_history = {
+            let inner = self.inner.lock();
+
+            if inner.autoplay && inner.tracks.is_empty() {
+                Some(inner.history.iter().cloned().collect::<Vec<_>>())
+            } else {
+                None
+            }
+        };
+
+        if let Some(history) = autoplay_history {
+            if let Some(track) = find_autoplay_track(&history).await {
+                info!("Autoplaying {:?}", track.name);
+
+                if let Err(e) = self.add(track, driver.clone(), chan_id, http.clone()).await {
+                    warn!("Could not autoplay track {:?}", e);
+                    self.dequeue(0);
+                }
+            }
+        }
+
+        // Nothing was preloaded when the front of the queue was only just re-added,
+        // e.g. when the queue with a single track in it is repeated, or it was moved there.
+        loop {
+            let front_track = {
+                let inner = self.inner.lock();
+                let current_track = inner.current_track.lock();
+
+                if current_track.is_some() {
+                    None
+                } else {
+                    inner.tracks.front().cloned()
+                }
+            };
 
-            info!("Queued track ended: {:?}.", ctx);
-            info!("{} tracks remain.", inner.tracks.len());
+            if let Some(front_track) = front_track {
+                match load_track(&front_track, self, driver.clone(), chan_id, http.clone()).await {
+                    Ok((track, handle)) => {
+                        let mut handler = driver.lock().await;
+                        handler.play(track);
+                        let mut inner = self.inner.lock();
+                        inner.apply_repeat_mode(&handle);
+                        let mut current_track = inner.current_track.lock();
+                        *current_track = Some(handle);
+                        break;
+                    }
+                    Err(e) => {
+                        warn!("Could not play track {:?}", e);
+                        let mut inner = self.inner.lock();
+                        inner.tracks.pop_front();
+                        continue;
+                    }
+                }
+            } else {
+                break;
+            }
         }
 
         loop {
             let next_track = {
-                let inner = self.remote_lock.lock();
+                let inner = self.inner.lock();
 
                 inner.tracks.get(1).cloned()
             };
 
             if let Some(next_track) = next_track {
-                let next_track_uuid = next_track.uuid;
-                let input = match get_input_from_queued_track(next_track).await {
-                    Ok(i) => i,
+                let (track, handle) = match load_track(
+                    &next_track,
+                    self,
+                    driver.clone(),
+                    chan_id,
+                    http.clone(),
+                )
+                .await
+                {
+                    Ok(loaded) => loaded,
                     Err(e) => {
                         warn!("Could not play track {:?}", e);
-                        let mut inner = self.remote_lock.lock();
+                        let mut inner = self.inner.lock();
                         inner.tracks.remove(1);
                         continue;
                     }
                 };
 
-                let (track, handle) = create_player_with_uuid(input, next_track_uuid);
-                let _ = handle.add_event(
-                    Event::Track(TrackEvent::End),
-                    Self {
-                        driver: self.driver.clone(),
-                        remote_lock: self.remote_lock.clone(),
-                        chan_id: self.chan_id,
-                        http: self.http.clone(),
-                    },
-                );
-                let _ = handle.add_event(
-                    Event::Delayed(Duration::from_millis(5)),
-                    TrackStartNotifier {
-                        chan_id: self.chan_id,
-                        http: self.http.clone(),
-                    },
-                );
                 let _ = handle.pause();
-                let mut handler = self.driver.lock().await;
+                let mut handler = driver.lock().await;
                 handler.play(track);
-                let inner = self.remote_lock.lock();
+                let inner = self.inner.lock();
                 let mut next_track = inner.next_track.lock();
                 *next_track = Some(handle);
                 break;
@@ -126,71 +546,72 @@ impl EventHandler for PlayNextTrack {
                 break;
             }
         }
-        None
     }
-}
 
-async fn get_input_from_queued_track(track: QueuedTrack) -> Result<Input> {
-    match Restartable::ytdl_search(&track.name, true).await {
-        Ok(r) => Ok(r.into()),
-        Err(e) => Err(anyhow!("{:?}", e)),
+    pub async fn add(
+        &self,
+        input: QueuedTrack,
+        driver: Arc<AsyncMutex<Call>>,
+        chan_id: ChannelId,
+        http: Arc<Http>,
+    ) -> anyhow::Result<()> {
+        let index = {
+            let mut inner = self.inner.lock();
+            let index = if inner.fair_queue {
+                fair_queue_index(&inner.tracks, input.requester)
+            } else {
+                inner.tracks.len()
+            };
+            inner.tracks.insert(index, input.clone());
+            inner.text_channel = Some(chan_id);
+            inner.changed = true;
+            inner.discard_stale_preload();
+            index
+        };
+
+        self.load_added(index, &input, driver, chan_id, http).await
     }
-}
 
-impl Queue {
-    pub async fn add(
+    /// Adds the track right after the current one, it is preloaded like any other next track.
+    pub async fn add_next(
         &self,
         input: QueuedTrack,
         driver: Arc<AsyncMutex<Call>>,
         chan_id: ChannelId,
         http: Arc<Http>,
     ) -> anyhow::Result<()> {
-        let (search, input_uuid) = {
+        let index = {
             let mut inner = self.inner.lock();
-            let search = input.name.clone();
-            let input_uuid = input.uuid;
-            inner.tracks.push_back(input);
-            (search, input_uuid)
+            let index = inner.tracks.len().min(1);
+            inner.tracks.insert(index, input.clone());
+            inner.text_channel = Some(chan_id);
+            inner.changed = true;
+            inner.discard_stale_preload();
+            index
         };
+
+        self.load_added(index, &input, driver, chan_id, http).await
+    }
+
+    /// Plays a track that was just added when it is the only one, or preloads it when it plays next.
+    async fn load_added(
+        &self,
+        index: usize,
+        input: &QueuedTrack,
+        driver: Arc<AsyncMutex<Call>>,
+        chan_id: ChannelId,
+        http: Arc<Http>,
+    ) -> anyhow::Result<()> {
         if self.len() == 1 {
-            let input = match Restartable::ytdl_search(&search, true).await {
-                Ok(input) => input,
-                Err(e) => return Err(anyhow!("{:?}", e)),
-            };
-            let (track, handle) = create_player_with_uuid(Input::from(input), input_uuid);
-            handle.add_event(
-                Event::Track(TrackEvent::End),
-                PlayNextTrack {
-                    driver: driver.clone(),
-                    remote_lock: self.inner.clone(),
-                    chan_id,
-                    http,
-                },
-            )?;
+            let (track, handle) = load_track(input, self, driver.clone(), chan_id, http).await?;
             let mut handler = driver.lock().await;
             handler.play(track);
-            let inner = self.inner.lock();
+            let mut inner = self.inner.lock();
+            inner.apply_repeat_mode(&handle);
             let mut current_track = inner.current_track.lock();
             *current_track = Some(handle);
-        } else if self.len() == 2 {
-            let input = match Restartable::ytdl_search(&search, true).await {
-                Ok(i) => i,
-                Err(e) => return Err(anyhow!("{:?}", e)),
-            };
-            let (track, handle) = create_player_with_uuid(Input::from(input), input_uuid);
-            handle.add_event(
-                Event::Track(TrackEvent::End),
-                PlayNextTrack {
-                    driver: driver.clone(),
-                    remote_lock: self.inner.clone(),
-                    chan_id,
-                    http: http.clone(),
-                },
-            )?;
-            handle.add_event(
-                Event::Track(TrackEvent::End),
-                TrackStartNotifier { chan_id, http },
-            )?;
+        } else if index == 1 {
+            let (track, handle) = load_track(input, self, driver.clone(), chan_id, http).await?;
             handle.pause()?;
             let mut handler = driver.lock().await;
             handler.play(track);
@@ -225,13 +646,20 @@ impl Queue {
         }
 
         inner.tracks.clear();
+        inner.skip_votes.clear();
+        inner.changed = true;
     }
 
+    /// The track is faded out first, the queue moves on once it has stopped.
     pub fn skip(&mut self) -> anyhow::Result<()> {
         let inner = self.inner.lock();
 
-        if let Some(handle) = inner.current_track.lock().as_ref() {
-            handle.stop()?;
+        if let Some(handle) = inner.current_track.lock().clone() {
+            tokio::spawn(async move {
+                if fade_out_and_stop(&handle).await.is_err() {
+                    let _ = handle.stop();
+                }
+            });
         }
         Ok(())
     }
@@ -248,6 +676,42 @@ impl Queue {
         inner.tracks.iter().cloned().collect()
     }
 
+    pub fn find(&self, uuid: Uuid) -> Option<QueuedTrack> {
+        let inner = self.inner.lock();
+
+        inner
+            .tracks
+            .iter()
+            .find(|track| track.uuid == uuid)
+            .cloned()
+    }
+
+    /// Fills in whatever is still unknown about a queued track from the metadata of its loaded input.
+    fn fill_metadata(&self, uuid: Uuid, metadata: &Metadata, speed: f64) {
+        let mut inner = self.inner.lock();
+
+        if let Some(track) = inner.tracks.iter_mut().find(|track| track.uuid == uuid) {
+            track.speed = speed;
+            track.title = track.title.take().or_else(|| metadata.title.clone());
+            track.url = track.url.take().or_else(|| metadata.source_url.clone());
+            track.duration = track.duration.or(metadata.duration);
+            track.live = track.live || track.duration.is_none();
+            track.uploader = track.uploader.take().or_else(|| metadata.channel.clone());
+            track.thumbnail = track
+                .thumbnail
+                .take()
+                .or_else(|| metadata.thumbnail.clone());
+        }
+    }
+
+    pub fn set_stream_title(&self, uuid: Uuid, title: &str) {
+        let mut inner = self.inner.lock();
+
+        if let Some(track) = inner.tracks.iter_mut().find(|track| track.uuid == uuid) {
+            track.stream_title = Some(title.to_string());
+        }
+    }
+
     pub fn dequeue(&self, index: usize) -> Option<QueuedTrack> {
         if index == 0 {
             let inner = self.inner.lock();
@@ -255,12 +719,6 @@ impl Queue {
             if let Some(handle) = current_track.as_ref() {
                 let _ = handle.stop();
             }
-        } else if index == 1 {
-            let inner = self.inner.lock();
-            let next_track = inner.next_track.lock();
-            if let Some(handle) = next_track.as_ref() {
-                let _ = handle.stop();
-            }
         }
 
         self.modify_queue(|vq| vq.remove(index))
@@ -271,8 +729,327 @@ impl Queue {
         F: FnOnce(&mut VecDeque<QueuedTrack>) -> O,
     {
         let mut inner = self.inner.lock();
+        inner.changed = true;
+
+        let output = func(&mut inner.tracks);
+        inner.discard_stale_preload();
+
+        output
+    }
+
+    /// Removes every upcoming track the predicate returns true for, it is called with each track's index.
+    /// The predicate also sees the currently playing track, but that one is always kept.
+    pub fn remove_upcoming<F>(&self, mut predicate: F) -> Vec<QueuedTrack>
+    where
+        F: FnMut(usize, &QueuedTrack) -> bool,
+    {
+        self.modify_queue(|vq| {
+            let mut removed = Vec::new();
+            let mut kept = VecDeque::with_capacity(vq.len());
+
+            for (idx, track) in vq.drain(..).enumerate() {
+                if predicate(idx, &track) && idx != 0 {
+                    removed.push(track);
+                } else {
+                    kept.push_back(track);
+                }
+            }
+
+            *vq = kept;
+
+            removed
+        })
+    }
+
+    /// Moves a track to another position, neither can be the currently playing track.
+    pub fn move_track(&self, from: usize, to: usize) -> Option<QueuedTrack> {
+        if from == 0 || to == 0 {
+            return None;
+        }
+
+        self.modify_queue(|vq| {
+            let track = vq.remove(from)?;
+            vq.insert(to.min(vq.len()), track.clone());
+            Some(track)
+        })
+    }
+
+    pub fn text_channel(&self) -> Option<ChannelId> {
+        let inner = self.inner.lock();
+
+        inner.text_channel
+    }
+
+    pub fn panel_message(&self) -> Option<MessageId> {
+        let inner = self.inner.lock();
+
+        inner.panel_message
+    }
+
+    pub fn set_panel_message(&self, message_id: Option<MessageId>) {
+        let mut inner = self.inner.lock();
+
+        inner.panel_message = message_id;
+    }
+
+    pub fn repeat_mode(&self) -> RepeatMode {
+        let inner = self.inner.lock();
+
+        inner.repeat_mode
+    }
+
+    /// Changes the repeat mode and applies it to the currently playing track, or the next one when nothing plays.
+    pub fn set_repeat_mode(&self, repeat_mode: RepeatMode) -> anyhow::Result<()> {
+        let mut inner = self.inner.lock();
+        inner.repeat_mode = repeat_mode;
+        inner.changed = true;
+
+        let current_track = inner.current_track.lock().clone();
+
+        inner.repeat_times_pending =
+            matches!(repeat_mode, RepeatMode::TrackTimes(_)) && current_track.is_none();
+
+        if let Some(handle) = current_track {
+            match repeat_mode {
+                RepeatMode::Track => handle.enable_loop()?,
+                RepeatMode::TrackTimes(times) => handle.loop_for(times)?,
+                RepeatMode::Off | RepeatMode::Queue => handle.disable_loop()?,
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn fair_queue(&self) -> bool {
+        let inner = self.inner.lock();
+
+        inner.fair_queue
+    }
+
+    /// Turning the fair queue on also reorders the tracks that are already queued.
+    pub fn set_fair_queue(&self, fair_queue: bool) {
+        {
+            let mut inner = self.inner.lock();
+
+            if inner.fair_queue == fair_queue {
+                return;
+            }
+
+            inner.fair_queue = fair_queue;
+        }
+
+        if fair_queue {
+            self.modify_queue(|vq| {
+                let mut reordered = VecDeque::with_capacity(vq.len());
+
+                for track in vq.drain(..) {
+                    let index = fair_queue_index(&reordered, track.requester);
+                    reordered.insert(index, track);
+                }
+
+                *vq = reordered;
+            });
+        }
+    }
+
+    pub fn autoplay(&self) -> bool {
+        let inner = self.inner.lock();
+
+        inner.autoplay
+    }
+
+    pub fn set_autoplay(&self, autoplay: bool) {
+        let mut inner = self.inner.lock();
+
+        inner.autoplay = autoplay;
+    }
+
+    pub fn filters(&self) -> Vec<AudioFilter> {
+        let inner = self.inner.lock();
+
+        inner.filters.clone()
+    }
+
+    /// The preloaded track is discarded since it was loaded with the old filters,
+    /// use `reload_current` to apply them to the playing track.
+    pub fn set_filters(&self, filters: Vec<AudioFilter>) {
+        let mut inner = self.inner.lock();
+        inner.filters = filters;
+
+        if let Some(handle) = inner.next_track.lock().take() {
+            let _ = handle.stop();
+        }
+    }
+
+    pub fn normalise(&self) -> bool {
+        let inner = self.inner.lock();
+
+        inner.normalise
+    }
+
+    /// Like the filters this discards the preloaded track, see `set_filters`.
+    pub fn set_normalise(&self, normalise: bool) {
+        let mut inner = self.inner.lock();
+
+        if inner.normalise == normalise {
+            return;
+        }
+
+        inner.normalise = normalise;
+
+        if let Some(handle) = inner.next_track.lock().take() {
+            let _ = handle.stop();
+        }
+    }
+
+    pub fn crossfade(&self) -> Duration {
+        let inner = self.inner.lock();
+
+        inner.crossfade
+    }
+
+    pub fn set_crossfade(&self, crossfade: Duration) {
+        let mut inner = self.inner.lock();
+
+        inner.crossfade = crossfade;
+    }
+
+    /// The paused track that plays next, as long as it is still the next one in the queue.
+    pub fn preloaded_track(&self) -> Option<TrackHandle> {
+        let inner = self.inner.lock();
+        let next_uuid = inner.tracks.get(1).map(|track| track.uuid);
+        let next_track = inner.next_track.lock();
+
+        next_track
+            .clone()
+            .filter(|handle| Some(handle.uuid()) == next_uuid)
+    }
+
+    /// Loads the playing track again and continues from the same position,
+    /// so changed filters and normalisation apply to it right away.
+    pub async fn reload_current(
+        &self,
+        driver: Arc<AsyncMutex<Call>>,
+        chan_id: ChannelId,
+        http: Arc<Http>,
+    ) -> Result<()> {
+        let (old_handle, queued_track) = {
+            let inner = self.inner.lock();
+            let current_track = inner.current_track.lock().clone();
+
+            match (current_track, inner.tracks.front()) {
+                (Some(handle), Some(track)) if handle.uuid() == track.uuid => {
+                    (handle, track.clone())
+                }
+                _ => return Ok(()),
+            }
+        };
+
+        let state = old_handle.get_info().await?;
 
-        func(&mut inner.tracks)
+        // The reloaded track gets a new uuid, so the old one ending does not advance the queue.
+        // It starts where the old one was in the track itself, which differs from the position under speed filters.
+        let reloaded = QueuedTrack {
+            uuid: Uuid::new_v4(),
+            start_time: if queued_track.live {
+                None
+            } else {
+                Some(state.position.mul_f64(queued_track.speed))
+            },
+            speed: speed_factor(&self.filters()),
+            ..queued_track.clone()
+        };
+
+        let (track, handle) = load_track(&reloaded, self, driver.clone(), chan_id, http).await?;
+
+        if let PlayMode::Pause = state.playing {
+            handle.pause()?;
+        }
+
+        match state.loops {
+            LoopState::Infinite => handle.enable_loop()?,
+            LoopState::Finite(times) if times > 0 => handle.loop_for(times)?,
+            LoopState::Finite(_) => {}
+        }
+
+        handle.set_volume(state.volume)?;
+
+        {
+            let mut inner = self.inner.lock();
+
+            match inner.tracks.front_mut() {
+                Some(front) if front.uuid == queued_track.uuid => {
+                    front.uuid = reloaded.uuid;
+                    front.speed = reloaded.speed;
+                }
+                // The track ended or was skipped while the new one was loading
+                _ => return Ok(()),
+            }
+
+            let mut current_track = inner.current_track.lock();
+            *current_track = Some(handle);
+        }
+
+        let mut handler = driver.lock().await;
+        handler.play(track);
+        let _ = old_handle.stop();
+
+        Ok(())
+    }
+
+    /// Adds a vote to skip the current track, returns the amount of votes
+    /// or `None` if the user already voted.
+    pub fn add_skip_vote(&self, user_id: UserId) -> Option<usize> {
+        let mut inner = self.inner.lock();
+
+        if inner.skip_votes.insert(user_id) {
+            Some(inner.skip_votes.len())
+        } else {
+            None
+        }
+    }
+
+    /// Recently played tracks, the newest first.
+    pub fn history(&self) -> Vec<QueuedTrack> {
+        let inner = self.inner.lock();
+
+        inner.history.iter().cloned().collect()
+    }
+
+    pub fn history_loaded(&self) -> bool {
+        let inner = self.inner.lock();
+
+        inner.history_loaded
+    }
+
+    /// Replaces the history with the one from the database, the newest first.
+    pub fn set_history(&self, history: Vec<QueuedTrack>) {
+        let mut inner = self.inner.lock();
+
+        inner.history = history.into_iter().take(HISTORY_LENGTH).collect();
+        inner.unsaved_history = 0;
+        inner.history_loaded = true;
+    }
+
+    /// Returns the history entries that still have to be saved, the oldest first.
+    pub fn take_unsaved_history(&self) -> Vec<QueuedTrack> {
+        let mut inner = self.inner.lock();
+
+        let unsaved = std::mem::take(&mut inner.unsaved_history);
+
+        inner.history.iter().take(unsaved).rev().cloned().collect()
+    }
+
+    /// Returns whether the queue was modified since the last call, and resets the flag.
+    pub fn take_changed(&self) -> bool {
+        let mut inner = self.inner.lock();
+
+        std::mem::take(&mut inner.changed)
+    }
+
+    /// Sets the flag again, so the next snapshot rewrites the tracks.
+    pub fn mark_changed(&self) {
+        self.inner.lock().changed = true;
     }
 }
 
@@ -289,3 +1066,66 @@ pub async fn get_queue_from_ctx_and_guild_id(ctx: &Context, guild_id: GuildId) -
 
     queue
 }
+
+/// Like `get_queue_from_ctx_and_guild_id`, for when the bot might not be in a voice channel.
+pub async fn get_existing_queue(ctx: &Context, guild_id: GuildId) -> Option<Queue> {
+    let data = ctx.data.read().await;
+    let queue_container = data.get::<QueueMap>().unwrap().clone();
+    let queue = queue_container.get(&guild_id).map(|queue| queue.clone());
+
+    queue
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use serenity::model::id::UserId;
+
+    use super::{fair_queue_index, QueuedTrack, RepeatMode};
+
+    fn requested_by(requesters: &[u64]) -> VecDeque<QueuedTrack> {
+        requesters
+            .iter()
+            .map(|&id| QueuedTrack::new(id.to_string(), Some(UserId(id))))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_repeat_mode() {
+        assert_eq!("off".parse(), Ok(RepeatMode::Off));
+        assert_eq!("Track".parse(), Ok(RepeatMode::Track));
+        assert_eq!("queue".parse(), Ok(RepeatMode::Queue));
+        assert_eq!("3".parse(), Ok(RepeatMode::TrackTimes(3)));
+        assert_eq!("0".parse(), Ok(RepeatMode::Off));
+        assert!("sometimes".parse::<RepeatMode>().is_err());
+    }
+
+    #[test]
+    fn test_repeat_mode_db_round_trip() {
+        for mode in [
+            RepeatMode::Off,
+            RepeatMode::Track,
+            RepeatMode::Queue,
+            RepeatMode::TrackTimes(4),
+        ] {
+            let (repeat_mode, repeat_times) = mode.to_db();
+            assert_eq!(RepeatMode::from_db(repeat_mode, repeat_times), mode);
+        }
+    }
+
+    #[test]
+    fn test_fair_queue_index() {
+        assert_eq!(fair_queue_index(&VecDeque::new(), Some(UserId(1))), 0);
+
+        // The playing track counts as the first turn of its requester
+        let tracks = requested_by(&[1, 1, 1]);
+        assert_eq!(fair_queue_index(&tracks, Some(UserId(2))), 1);
+        assert_eq!(fair_queue_index(&tracks, Some(UserId(1))), 3);
+
+        let tracks = requested_by(&[1, 2, 1, 1]);
+        assert_eq!(fair_queue_index(&tracks, Some(UserId(2))), 3);
+        assert_eq!(fair_queue_index(&tracks, Some(UserId(3))), 2);
+        assert_eq!(fair_queue_index(&tracks, None), 2);
+    }
+}
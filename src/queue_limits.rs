@@ -0,0 +1,107 @@
+use std::{fmt, time::Duration};
+
+use crate::commands::util::format_duration_to_mm_ss;
+
+/// Limits on what users can add to the queue of a guild, `None` means unlimited.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct QueueLimits {
+    pub max_user_tracks: Option<u32>,
+    pub max_queue_length: Option<u32>,
+    pub max_track_length: Option<Duration>,
+    pub max_playlist_size: Option<u32>,
+    /// Whether DJs and admins can go over the limits
+    pub exempt_djs: bool,
+}
+
+impl Default for QueueLimits {
+    fn default() -> Self {
+        Self {
+            max_user_tracks: None,
+            max_queue_length: None,
+            max_track_length: None,
+            max_playlist_size: None,
+            exempt_djs: true,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum LimitExceeded {
+    UserTracks(u32),
+    QueueLength(u32),
+    TrackLength(Duration),
+}
+
+impl QueueLimits {
+    /// Checks if a track can be added to a queue of the given length, in which the user already has `user_tracks`.
+    pub fn check(
+        &self,
+        queue_length: usize,
+        user_tracks: usize,
+        track_length: Option<Duration>,
+    ) -> Result<(), LimitExceeded> {
+        if let Some(max) = self.max_queue_length {
+            if queue_length >= max as usize {
+                return Err(LimitExceeded::QueueLength(max));
+            }
+        }
+
+        if let Some(max) = self.max_user_tracks {
+            if user_tracks >= max as usize {
+                return Err(LimitExceeded::UserTracks(max));
+            }
+        }
+
+        if let (Some(max), Some(track_length)) = (self.max_track_length, track_length) {
+            if track_length > max {
+                return Err(LimitExceeded::TrackLength(max));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UserTracks(max) => write!(f, "You can only have {max} songs in the queue"),
+            Self::QueueLength(max) => write!(f, "The queue is full, it can hold {max} songs"),
+            Self::TrackLength(max) => write!(
+                f,
+                "Songs can be at most {} long",
+                format_duration_to_mm_ss(*max)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{LimitExceeded, QueueLimits};
+
+    #[test]
+    fn test_queue_limits() {
+        let limits = QueueLimits {
+            max_user_tracks: Some(2),
+            max_queue_length: Some(10),
+            max_track_length: Some(Duration::from_secs(600)),
+            ..QueueLimits::default()
+        };
+
+        assert_eq!(limits.check(3, 1, Some(Duration::from_secs(200))), Ok(()));
+        assert_eq!(limits.check(3, 1, None), Ok(()));
+        assert_eq!(limits.check(3, 2, None), Err(LimitExceeded::UserTracks(2)));
+        assert_eq!(
+            limits.check(10, 0, None),
+            Err(LimitExceeded::QueueLength(10))
+        );
+        assert_eq!(
+            limits.check(3, 0, Some(Duration::from_secs(601))),
+            Err(LimitExceeded::TrackLength(Duration::from_secs(600)))
+        );
+        assert_eq!(QueueLimits::default().check(1000, 1000, None), Ok(()));
+    }
+}
@@ -0,0 +1,29 @@
+use serenity::model::prelude::*;
+use sqlx::PgPool;
+
+use crate::{
+    db::{get_autoplay, get_crossfade, get_fair_queue, get_normalise},
+    history::load_history,
+    queue::Queue,
+};
+
+/// Applies the settings admins saved for the guild to a queue that was just created.
+pub async fn load_queue_settings(
+    pool: &PgPool,
+    guild_id: GuildId,
+    queue: &Queue,
+) -> anyhow::Result<()> {
+    queue.set_fair_queue(get_fair_queue(pool, guild_id.into()).await?);
+    queue.set_normalise(get_normalise(pool, guild_id.into()).await?);
+    queue.set_crossfade(get_crossfade(pool, guild_id.into()).await?);
+
+    let autoplay = get_autoplay(pool, guild_id.into()).await?;
+    queue.set_autoplay(autoplay);
+
+    // Autoplay falls back to the history, which is otherwise only read when it is shown
+    if autoplay {
+        load_history(pool, guild_id, queue).await?;
+    }
+
+    Ok(())
+}
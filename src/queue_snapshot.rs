@@ -0,0 +1,278 @@
+use std::{
+    convert::{TryFrom, TryInto},
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use serenity::{client::Cache, model::prelude::*, prelude::*};
+use songbird::{tracks::PlayMode, Event};
+use tracing::{info, warn};
+
+use crate::{
+    data::PoolContainer,
+    db::{
+        delete_queue_snapshot, get_queue_snapshots, get_queued_tracks, save_queue_snapshot,
+        QueueSnapshot, SnapshotTrack,
+    },
+    history::save_history,
+    queue::{Queue, QueueMap, QueuedTrack, RepeatMode},
+    queue_settings::load_queue_settings,
+    voice_events::ChannelIdleChecker,
+};
+
+pub const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Writes every queue that changed or is currently playing to the database.
+/// When `force` is set every queue is written, regardless of its state.
+pub async fn save_queue_snapshots(data: &TypeMap, cache: &Cache, force: bool) {
+    let pool = data.get::<PoolContainer>().unwrap();
+    let queue_container = data.get::<QueueMap>().unwrap().clone();
+
+    let queues: Vec<(GuildId, Queue)> = queue_container
+        .iter()
+        .map(|entry| (*entry.key(), entry.value().clone()))
+        .collect();
+
+    let bot_id = cache.current_user_id().await;
+
+    for (guild_id, queue) in queues {
+        if let Err(e) = save_history(pool, guild_id, &queue).await {
+            warn!("Could not save history for {}: {:?}", guild_id, e);
+        }
+
+        let changed = queue.take_changed();
+
+        let current = { queue.current().lock().clone() };
+
+        let track_state = match current {
+            Some(handle) => handle.get_info().await.ok(),
+            None => None,
+        };
+
+        let is_playing = track_state
+            .as_ref()
+            .map_or(false, |state| matches!(state.playing, PlayMode::Play));
+
+        if !(force || changed || is_playing) {
+            continue;
+        }
+
+        let tracks = queue.current_queue();
+
+        let voice_channel = guild_id
+            .to_guild_cached(cache)
+            .await
+            .and_then(|guild| guild.voice_states.get(&bot_id).and_then(|v| v.channel_id));
+
+        let result = match (voice_channel, queue.text_channel()) {
+            (Some(voice_channel), Some(text_channel)) if !tracks.is_empty() => {
+                let (repeat_mode, repeat_times) = queue.repeat_mode().to_db();
+
+                let snapshot = QueueSnapshot {
+                    guild_id: guild_id.into(),
+                    voice_channel_id: voice_channel.into(),
+                    text_channel_id: text_channel.into(),
+                    // Saved as track time, filters are not kept across restarts so the speed may differ
+                    position_ms: track_state
+                        .as_ref()
+                        .map(|state| {
+                            let speed = tracks.first().map_or(1.0, |track| track.speed);
+                            i64::try_from(state.position.mul_f64(speed).as_millis())
+                                .unwrap_or_default()
+                        })
+                        .unwrap_or_default(),
+                    volume: track_state.as_ref().map_or(1.0, |state| state.volume),
+                    repeat_mode,
+                    repeat_times,
+                };
+
+                let tracks: Vec<SnapshotTrack> = tracks
+                    .into_iter()
+                    .map(|track| SnapshotTrack {
+                        name: track.name,
+                        requester_id: track.requester.map(|requester| requester.into()),
+                        title: track.title,
+                        live: track.live,
+                        radio: track.radio,
+                        stream_title: track.stream_title,
+                    })
+                    .collect();
+
+                // Only the position moves while nothing else changes, the tracks stay as they were saved
+                let tracks = if force || changed {
+                    Some(tracks.as_slice())
+                } else {
+                    None
+                };
+
+                save_queue_snapshot(pool, &snapshot, tracks).await
+            }
+            _ => {
+                // The tracks have to be written again once the bot is back in a voice channel
+                if !tracks.is_empty() {
+                    queue.mark_changed();
+                }
+                delete_queue_snapshot(pool, guild_id.into()).await
+            }
+        };
+
+        if let Err(e) = result {
+            warn!("Could not save queue snapshot for {}: {:?}", guild_id, e);
+            queue.mark_changed();
+        }
+    }
+}
+
+/// Rejoins the voice channels and refills the queues saved for the given guilds.
+pub async fn restore_queues(ctx: &Context, guild_ids: &[GuildId]) -> anyhow::Result<()> {
+    let snapshots = {
+        let data = ctx.data.read().await;
+        let pool = data.get::<PoolContainer>().unwrap();
+        let guild_ids: Vec<i64> = guild_ids.iter().map(|&guild_id| guild_id.into()).collect();
+
+        get_queue_snapshots(pool, &guild_ids).await?
+    };
+
+    for snapshot in snapshots {
+        let guild_id = snapshot.guild_id;
+
+        if let Err(e) = restore_queue(ctx, snapshot).await {
+            warn!("Could not restore queue for {}: {:?}", guild_id, e);
+
+            let data = ctx.data.read().await;
+            let pool = data.get::<PoolContainer>().unwrap();
+
+            // One broken snapshot should not keep the other guilds from being restored
+            if let Err(e) = delete_queue_snapshot(pool, guild_id).await {
+                warn!("Could not delete queue snapshot for {}: {:?}", guild_id, e);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn restore_queue(ctx: &Context, snapshot: QueueSnapshot) -> anyhow::Result<()> {
+    let guild_id = GuildId(snapshot.guild_id.try_into()?);
+    let voice_channel = ChannelId(snapshot.voice_channel_id.try_into()?);
+    let text_channel = ChannelId(snapshot.text_channel_id.try_into()?);
+
+    let tracks = {
+        let data = ctx.data.read().await;
+        let pool = data.get::<PoolContainer>().unwrap();
+        get_queued_tracks(pool, snapshot.guild_id).await?
+    };
+
+    if tracks.is_empty() {
+        return Err(anyhow!("Snapshot has no tracks"));
+    }
+
+    let manager = songbird::get(ctx).await.unwrap().clone();
+
+    if manager.get(guild_id).is_some() {
+        return Ok(());
+    }
+
+    let (handler_lock, success) = manager.join(guild_id, voice_channel).await;
+    success?;
+
+    let queue = {
+        let mut handler = handler_lock.lock().await;
+        let data = ctx.data.read().await;
+        let queue_container = data.get::<QueueMap>().unwrap().clone();
+        let queue = queue_container.entry(guild_id).or_default().clone();
+
+        handler.add_global_event(
+            Event::Periodic(Duration::from_secs(60), None),
+            ChannelIdleChecker {
+                handler_lock: handler_lock.clone(),
+                elapsed: Default::default(),
+                chan_id: text_channel,
+                guild_id,
+                http: ctx.http.clone(),
+                cache: ctx.cache.clone(),
+                queue: queue.clone(),
+            },
+        );
+
+        let pool = data.get::<PoolContainer>().unwrap();
+        load_queue_settings(pool, guild_id, &queue).await?;
+
+        queue
+    };
+
+    // The position is saved as track time, so it is seeked to through the track's own speed
+    let position = Duration::from_millis(snapshot.position_ms.try_into().unwrap_or_default());
+
+    for (idx, track) in tracks.into_iter().enumerate() {
+        let requester = track
+            .requester_id
+            .and_then(|requester_id| requester_id.try_into().ok())
+            .map(UserId);
+
+        let queued_track = QueuedTrack {
+            title: track.title,
+            live: track.live,
+            radio: track.radio,
+            stream_title: track.stream_title,
+            start_time: if idx == 0 && !track.live {
+                Some(position)
+            } else {
+                None
+            },
+            ..QueuedTrack::new(track.name, requester)
+        };
+        let uuid = queued_track.uuid;
+
+        if let Err(e) = queue
+            .add(
+                queued_track,
+                handler_lock.clone(),
+                text_channel,
+                ctx.http.clone(),
+            )
+            .await
+        {
+            // A track that can not be loaded anymore should not take the rest of the queue down with it
+            warn!("Could not restore track for {}: {:?}", guild_id, e);
+            queue.modify_queue(|vq| vq.retain(|track| track.uuid != uuid));
+        }
+    }
+
+    if queue.current_queue().is_empty() {
+        {
+            let data = ctx.data.read().await;
+            let queue_container = data.get::<QueueMap>().unwrap().clone();
+            queue_container.remove(&guild_id);
+
+            let mut handler = handler_lock.lock().await;
+            handler.remove_all_global_events();
+        }
+
+        manager.remove(guild_id).await?;
+
+        return Err(anyhow!("None of the snapshot's tracks could be loaded"));
+    }
+
+    let current = { queue.current().lock().clone() };
+
+    if let Some(handle) = current {
+        handle.set_volume(snapshot.volume)?;
+    }
+
+    queue.set_repeat_mode(RepeatMode::from_db(
+        snapshot.repeat_mode,
+        snapshot.repeat_times,
+    ))?;
+
+    info!("Restored queue for {}", guild_id);
+
+    text_channel
+        .say(
+            &ctx.http,
+            "I was restarted, picking the queue back up where it left off",
+        )
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,387 @@
+use std::{
+    io::{self, Write},
+    process::Stdio,
+    sync::mpsc,
+    time::Duration,
+};
+
+use reqwest::header::CONTENT_TYPE;
+use serenity::async_trait;
+use songbird::input::{
+    children_to_reader, error::Result as InputResult, ffmpeg_optioned, restartable::Restart, Codec,
+    Container, Input, Metadata,
+};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{playlists::get_youtube_video_id, queue::Queue};
+
+lazy_static::lazy_static! {
+    static ref CLIENT: reqwest::Client = reqwest::Client::new();
+}
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How many chunks of a station are held for ffmpeg, later ones are dropped until it reads again.
+const BUFFERED_CHUNKS: usize = 64;
+
+#[derive(Debug)]
+pub struct RadioStream {
+    /// The name the station gives itself in the `icy-name` header.
+    pub name: Option<String>,
+}
+
+/// Youtube and twitch live streams are played through yt-dlp, so they are never probed.
+fn is_ytdl_site(url: &str) -> bool {
+    get_youtube_video_id(url).is_some()
+        || url.contains("youtube.com/")
+        || url.contains("twitch.tv/")
+}
+
+/// Checks whether the link is an endless audio stream, like an icecast or shoutcast station or a HLS playlist.
+/// Only the headers are read, the connection is closed afterwards.
+pub async fn probe_radio_stream(url: &str) -> Option<RadioStream> {
+    if !url.starts_with("http") || is_ytdl_site(url) {
+        return None;
+    }
+
+    let res = CLIENT
+        .get(url)
+        .header("Icy-MetaData", "1")
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?;
+
+    let headers = res.headers();
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_string())
+    };
+
+    let name = header("icy-name").filter(|name| !name.is_empty());
+    let content_type = header(CONTENT_TYPE.as_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let is_icy = name.is_some() || headers.contains_key("icy-metaint");
+    let is_hls = content_type.contains("mpegurl");
+    let is_endless_audio = (content_type.starts_with("audio/")
+        || content_type == "application/ogg")
+        && res.content_length().is_none();
+
+    if is_icy || is_hls || is_endless_audio {
+        Some(RadioStream { name })
+    } else {
+        None
+    }
+}
+
+/// Reads `StreamTitle='Artist - Song';` out of an icy metadata block.
+pub fn parse_stream_title(metadata: &str) -> Option<&str> {
+    let (_, rest) = metadata.split_once("StreamTitle='")?;
+    let title = rest.split("';").next()?.trim();
+
+    if title.is_empty() {
+        None
+    } else {
+        Some(title)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum IcyState {
+    /// How much audio is left before the next metadata block.
+    Audio(usize),
+    Length,
+    Metadata(usize),
+}
+
+/// Splits the metadata blocks out of an icy stream, every `metaint` bytes of audio are followed
+/// by a length byte and that many 16 byte chunks of metadata.
+struct IcyReader {
+    metaint: usize,
+    state: IcyState,
+    metadata: Vec<u8>,
+}
+
+impl IcyReader {
+    fn new(metaint: usize) -> Self {
+        Self {
+            metaint,
+            state: IcyState::Audio(metaint),
+            metadata: Vec::new(),
+        }
+    }
+
+    /// Returns the metadata blocks that were completed by the data, empty blocks mean nothing changed.
+    /// The audio around the blocks is added to `audio`.
+    fn feed(&mut self, mut data: &[u8], audio: &mut Vec<u8>) -> Vec<String> {
+        let mut blocks = Vec::new();
+
+        while !data.is_empty() {
+            match self.state {
+                IcyState::Audio(left) => {
+                    let read = left.min(data.len());
+                    audio.extend_from_slice(&data[..read]);
+                    data = &data[read..];
+
+                    self.state = if read == left {
+                        IcyState::Length
+                    } else {
+                        IcyState::Audio(left - read)
+                    };
+                }
+                IcyState::Length => {
+                    let length = data[0] as usize * 16;
+                    data = &data[1..];
+
+                    if length == 0 {
+                        blocks.push(String::new());
+                        self.state = IcyState::Audio(self.metaint);
+                    } else {
+                        self.metadata.clear();
+                        self.state = IcyState::Metadata(length);
+                    }
+                }
+                IcyState::Metadata(left) => {
+                    let read = left.min(data.len());
+                    self.metadata.extend_from_slice(&data[..read]);
+                    data = &data[read..];
+
+                    if read == left {
+                        let block = String::from_utf8_lossy(&self.metadata);
+                        blocks.push(block.trim_end_matches('\0').to_string());
+                        self.state = IcyState::Audio(self.metaint);
+                    } else {
+                        self.state = IcyState::Metadata(left - read);
+                    }
+                }
+            }
+        }
+
+        blocks
+    }
+}
+
+/// Plays a radio station through ffmpeg. The titles the station sends are cut out of the audio
+/// before it is handed to ffmpeg, so only one connection to the station is needed.
+pub struct RadioSource {
+    url: String,
+    filter_chain: Option<String>,
+    queue: Queue,
+    uuid: Uuid,
+}
+
+impl RadioSource {
+    pub fn new(url: String, filter_chain: Option<String>, queue: Queue, uuid: Uuid) -> Self {
+        Self {
+            url,
+            filter_chain,
+            queue,
+            uuid,
+        }
+    }
+
+    fn ffmpeg_args(&self) -> Vec<&str> {
+        let mut args = Vec::new();
+
+        if let Some(chain) = &self.filter_chain {
+            args.extend(["-af", chain.as_str()]);
+        }
+
+        args.extend([
+            "-f",
+            "s16le",
+            "-ac",
+            "2",
+            "-ar",
+            "48000",
+            "-acodec",
+            "pcm_f32le",
+            "-",
+        ]);
+
+        args
+    }
+}
+
+#[async_trait]
+impl Restart for RadioSource {
+    /// Stations can not be seeked, restarting connects to the station again.
+    async fn call_restart(&mut self, _time: Option<Duration>) -> InputResult<Input> {
+        let res = CLIENT
+            .get(&self.url)
+            .header("Icy-MetaData", "1")
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+
+        let header = |name: &str| {
+            res.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.trim().to_lowercase())
+        };
+
+        let metaint = header("icy-metaint").and_then(|value| value.parse::<usize>().ok());
+        let is_hls = header(CONTENT_TYPE.as_str()).map_or(false, |value| value.contains("mpegurl"));
+
+        // HLS playlists only point to the audio, ffmpeg has to follow them itself
+        if is_hls {
+            drop(res);
+
+            let mut input = ffmpeg_optioned(
+                &self.url,
+                &[
+                    "-reconnect",
+                    "1",
+                    "-reconnect_streamed",
+                    "1",
+                    "-reconnect_delay_max",
+                    "5",
+                ],
+                &self.ffmpeg_args(),
+            )
+            .await?;
+            input.stereo = true;
+
+            return Ok(input);
+        }
+
+        let mut ffmpeg = std::process::Command::new("ffmpeg")
+            .args(["-i", "-"])
+            .args(self.ffmpeg_args())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let mut stdin = ffmpeg
+            .stdin
+            .take()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "ffmpeg has no stdin"))?;
+
+        let (audio_tx, audio_rx) = mpsc::sync_channel::<Vec<u8>>(BUFFERED_CHUNKS);
+
+        // Writing to ffmpeg blocks, it stops once ffmpeg is killed along with the track
+        std::thread::spawn(move || {
+            for audio in audio_rx {
+                if stdin.write_all(&audio).is_err() {
+                    break;
+                }
+            }
+        });
+
+        // Not every station sends titles
+        let reader = metaint.filter(|metaint| *metaint > 0).map(IcyReader::new);
+
+        tokio::spawn(feed_station(
+            res,
+            reader,
+            audio_tx,
+            self.queue.clone(),
+            self.uuid,
+        ));
+
+        Ok(Input::new(
+            true,
+            children_to_reader::<f32>(vec![ffmpeg]),
+            Codec::FloatPcm,
+            Container::Raw,
+            None,
+        ))
+    }
+
+    async fn lazy_init(&mut self) -> InputResult<(Option<Metadata>, Codec, Container)> {
+        Ok((None, Codec::FloatPcm, Container::Raw))
+    }
+}
+
+/// Hands the audio of the station to ffmpeg and keeps the title of the track up to date,
+/// until the station stops sending or ffmpeg is gone.
+async fn feed_station(
+    mut res: reqwest::Response,
+    mut reader: Option<IcyReader>,
+    audio_tx: mpsc::SyncSender<Vec<u8>>,
+    queue: Queue,
+    uuid: Uuid,
+) {
+    loop {
+        let chunk = match res.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break,
+            Err(e) => {
+                warn!("Stopped reading station {}, {:?}", res.url(), e);
+                break;
+            }
+        };
+
+        let audio = match &mut reader {
+            Some(reader) => {
+                let mut audio = Vec::with_capacity(chunk.len());
+
+                for block in reader.feed(&chunk, &mut audio) {
+                    if let Some(title) = parse_stream_title(&block) {
+                        queue.set_stream_title(uuid, title);
+                    }
+                }
+
+                audio
+            }
+            None => chunk.to_vec(),
+        };
+
+        // While the track is paused ffmpeg is not read, the station goes on so its audio is dropped
+        match audio_tx.try_send(audio) {
+            Ok(()) | Err(mpsc::TrySendError::Full(_)) => {}
+            Err(mpsc::TrySendError::Disconnected(_)) => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_stream_title, IcyReader};
+
+    #[test]
+    fn test_parse_stream_title() {
+        assert_eq!(
+            parse_stream_title("StreamTitle='Queen - Don't Stop Me Now';StreamUrl='';"),
+            Some("Queen - Don't Stop Me Now")
+        );
+        assert_eq!(parse_stream_title("StreamTitle='';"), None);
+        assert_eq!(parse_stream_title("StreamUrl='http://example.com';"), None);
+    }
+
+    #[test]
+    fn test_icy_reader() {
+        let metadata = b"StreamTitle='Song';\0\0\0\0\0\0\0\0\0\0\0\0\0";
+        assert_eq!(metadata.len(), 32);
+
+        let mut stream = vec![0u8; 4];
+        stream.push(2);
+        stream.extend_from_slice(metadata);
+        stream.extend_from_slice(&[0; 4]);
+        stream.push(0);
+        stream.extend_from_slice(&[0; 2]);
+
+        let mut reader = IcyReader::new(4);
+        let mut audio = Vec::new();
+
+        // Split in awkward places to make sure blocks can span chunks
+        let mut blocks = reader.feed(&stream[..10], &mut audio);
+        blocks.extend(reader.feed(&stream[10..], &mut audio));
+
+        assert_eq!(
+            blocks,
+            vec!["StreamTitle='Song';".to_string(), String::new()]
+        );
+        assert_eq!(audio, vec![0; 10]);
+    }
+}
@@ -0,0 +1,70 @@
+use std::{fmt, str::FromStr};
+
+/// How many of the listeners have to vote before a track is skipped.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum SkipThreshold {
+    Percentage(u8),
+    Count(u32),
+}
+
+impl Default for SkipThreshold {
+    fn default() -> Self {
+        Self::Percentage(50)
+    }
+}
+
+impl SkipThreshold {
+    pub fn required_votes(self, listeners: usize) -> usize {
+        let required = match self {
+            Self::Percentage(percentage) => (listeners * percentage as usize + 99) / 100,
+            Self::Count(count) => (count as usize).min(listeners),
+        };
+
+        required.max(1)
+    }
+}
+
+impl FromStr for SkipThreshold {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(percentage) = s.strip_suffix('%') {
+            match percentage.trim().parse() {
+                Ok(percentage @ 1..=100) => Ok(Self::Percentage(percentage)),
+                _ => Err("The percentage has to be from 1 to 100"),
+            }
+        } else {
+            match s.parse() {
+                Ok(count) if count > 0 => Ok(Self::Count(count)),
+                _ => Err("Must be a number of votes, or a percentage like 50%"),
+            }
+        }
+    }
+}
+
+impl fmt::Display for SkipThreshold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Percentage(percentage) => write!(f, "{percentage}% of listeners"),
+            Self::Count(count) => write!(f, "{count} votes"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SkipThreshold;
+
+    #[test]
+    fn test_skip_threshold() {
+        assert_eq!("50%".parse(), Ok(SkipThreshold::Percentage(50)));
+        assert_eq!("3".parse(), Ok(SkipThreshold::Count(3)));
+        assert!("0".parse::<SkipThreshold>().is_err());
+        assert!("150%".parse::<SkipThreshold>().is_err());
+
+        assert_eq!(SkipThreshold::Percentage(50).required_votes(3), 2);
+        assert_eq!(SkipThreshold::Percentage(50).required_votes(4), 2);
+        assert_eq!(SkipThreshold::Count(5).required_votes(2), 2);
+        assert_eq!(SkipThreshold::Percentage(10).required_votes(0), 1);
+    }
+}
@@ -0,0 +1,994 @@
+use serenity::{
+    builder::CreateApplicationCommands,
+    framework::standard::{CommandResult, Reason},
+    model::{
+        interactions::{
+            application_command::{
+                ApplicationCommand, ApplicationCommandInteraction,
+                ApplicationCommandInteractionDataOption,
+                ApplicationCommandInteractionDataOptionValue, ApplicationCommandOptionType,
+            },
+            InteractionResponseType,
+        },
+        prelude::*,
+    },
+    prelude::*,
+};
+use tracing::{info, warn};
+
+use crate::{
+    checks::{check_admin_only, check_dj_only, check_not_blacklisted},
+    commands::{
+        autoplay::toggle_autoplay,
+        crossfade::change_crossfade,
+        dj_only::toggle_dj_only,
+        fair_queue::toggle_fair_queue,
+        favourites::{grab_track, remove_from_favourites, show_favourites},
+        filter::change_filter,
+        history::{play_previous, replay_track, show_history},
+        join::join_channel,
+        library::{play_library_track, search_library_tracks, show_library},
+        loop_command::set_repeat_mode,
+        lyrics::show_lyrics,
+        move_command::{move_track, swap_tracks},
+        mute::toggle_mute,
+        normalise::toggle_normalise,
+        now_playing::show_now_playing,
+        pause::toggle_pause,
+        perms::{list_perms, set_perms},
+        play::play_track,
+        play_next::play_track_next,
+        playlist::{
+            add_to_playlist, create_user_playlist, delete_user_playlist, play_playlist,
+            remove_from_playlist, save_queue_as_playlist, show_playlist, toggle_playlist_shared,
+        },
+        queue::show_queue,
+        queue_limits::change_queue_limits,
+        remove::{clear_queue, remove_duplicate_tracks, remove_tracks, remove_user_tracks},
+        restart::restart_track,
+        resume::resume_track,
+        search::search_tracks,
+        seek::{seek_backward, seek_forward, seek_to},
+        shuffle::shuffle_tracks,
+        skip::skip_track,
+        skip_to::skip_to_track,
+        station::{add_station, play_station, remove_station, show_stations},
+        stop::stop_playing,
+        volume::set_volume,
+        voteskip::{change_skip_threshold, vote_skip},
+    },
+    consts::INSUFFICIENT_PERMISSIONS_MESSAGE,
+    invoker::Invoker,
+    queue::RepeatMode,
+};
+
+fn create_commands(commands: &mut CreateApplicationCommands) -> &mut CreateApplicationCommands {
+    commands
+        .create_application_command(|c| {
+            c.name("play")
+                .description("Adds a new song to the queue")
+                .create_option(|o| {
+                    o.name("song")
+                        .description("The name of the song, or a link to it")
+                        .kind(ApplicationCommandOptionType::String)
+                        .required(true)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("search")
+                .description("Searches youtube and lets you pick which of the results to add to the queue")
+                .create_option(|o| {
+                    o.name("query")
+                        .description("The name of the song to search for")
+                        .kind(ApplicationCommandOptionType::String)
+                        .required(true)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("playnext")
+                .description(
+                    "Adds a song to the front of the queue, so it plays after the current one",
+                )
+                .create_option(|o| {
+                    o.name("song")
+                        .description("The name of the song, or a link to it")
+                        .kind(ApplicationCommandOptionType::String)
+                        .required(true)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("skip")
+                .description("Skips the currently playing track")
+        })
+        .create_application_command(|c| {
+            c.name("skipto")
+                .description("Skips to a song in the queue, removing every song before it")
+                .create_option(|o| {
+                    o.name("index")
+                        .description("The index of the song to skip to, use /queue to find it")
+                        .kind(ApplicationCommandOptionType::Integer)
+                        .required(true)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("voteskip")
+                .description("Votes to skip the currently playing track")
+        })
+        .create_application_command(|c| {
+            c.name("stop")
+                .description("Stops the currently playing track, and clears the queue")
+        })
+        .create_application_command(|c| {
+            c.name("pause")
+                .description("Pauses/Resumes the currently playing track")
+        })
+        .create_application_command(|c| c.name("resume").description("Resumes a paused track"))
+        .create_application_command(|c| {
+            c.name("restart")
+                .description("Restarts the currently playing track")
+        })
+        .create_application_command(|c| {
+            c.name("seek")
+                .description("Seeks to a position in the currently playing track")
+                .create_option(|o| {
+                    o.name("position")
+                        .description("The position to seek to, like mm:ss or hh:mm:ss")
+                        .kind(ApplicationCommandOptionType::String)
+                        .required(true)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("forward")
+                .description("Skips forward in the currently playing track")
+                .create_option(|o| {
+                    o.name("seconds")
+                        .description("How many seconds to skip forward")
+                        .kind(ApplicationCommandOptionType::Integer)
+                        .required(true)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("rewind")
+                .description("Goes back in the currently playing track")
+                .create_option(|o| {
+                    o.name("seconds")
+                        .description("How many seconds to go back")
+                        .kind(ApplicationCommandOptionType::Integer)
+                        .required(true)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("loop")
+                .description(
+                    "Sets what gets repeated, or enables/disables a loop for the current track",
+                )
+                .create_option(|o| {
+                    o.name("mode")
+                        .description("What to repeat")
+                        .kind(ApplicationCommandOptionType::String)
+                        .required(false)
+                        .add_string_choice("Off", "off")
+                        .add_string_choice("Track", "track")
+                        .add_string_choice("Queue", "queue")
+                })
+                .create_option(|o| {
+                    o.name("times")
+                        .description("How many times to repeat the current track")
+                        .kind(ApplicationCommandOptionType::Integer)
+                        .required(false)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("autoplay")
+                .description("Enables/Disables autoplay, which adds related songs once the queue runs out")
+        })
+        .create_application_command(|c| {
+            c.name("queue")
+                .description("Shows the currently queued tracks")
+                .create_option(|o| {
+                    o.name("page")
+                        .description("The page of the queue to show")
+                        .kind(ApplicationCommandOptionType::Integer)
+                        .required(false)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("history")
+                .description("Shows the recently played tracks")
+        })
+        .create_application_command(|c| {
+            c.name("previous")
+                .description("Plays the previous track again")
+        })
+        .create_application_command(|c| {
+            c.name("replay")
+                .description("Adds a track from the history back to the queue")
+                .create_option(|o| {
+                    o.name("index")
+                        .description("The index of the track, use /history to find it")
+                        .kind(ApplicationCommandOptionType::Integer)
+                        .required(true)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("playlist")
+                .description("Manages playlists saved on the bot")
+                .create_option(|o| {
+                    o.name("list")
+                        .description("Lists your playlists and the ones shared with the server")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                })
+                .create_option(|o| {
+                    o.name("create")
+                        .description("Creates an empty playlist")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                        .create_sub_option(|s| {
+                            s.name("name")
+                                .description("The name of the playlist")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                        })
+                        .create_sub_option(|s| {
+                            s.name("shared")
+                                .description("Lets everyone in the server play it")
+                                .kind(ApplicationCommandOptionType::Boolean)
+                                .required(false)
+                        })
+                })
+                .create_option(|o| {
+                    o.name("add")
+                        .description("Adds a song to a playlist")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                        .create_sub_option(|s| {
+                            s.name("name")
+                                .description("The name of the playlist")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                        })
+                        .create_sub_option(|s| {
+                            s.name("song")
+                                .description("The name or url of the song, the current song if left out")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(false)
+                        })
+                })
+                .create_option(|o| {
+                    o.name("remove")
+                        .description("Removes a song from a playlist")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                        .create_sub_option(|s| {
+                            s.name("name")
+                                .description("The name of the playlist")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                        })
+                        .create_sub_option(|s| {
+                            s.name("index")
+                                .description("The index of the song, use /playlist show to find it")
+                                .kind(ApplicationCommandOptionType::Integer)
+                                .required(true)
+                        })
+                })
+                .create_option(|o| {
+                    o.name("show")
+                        .description("Shows the songs in a playlist")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                        .create_sub_option(|s| {
+                            s.name("name")
+                                .description("The name of the playlist")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                        })
+                })
+                .create_option(|o| {
+                    o.name("play")
+                        .description("Adds every song of a playlist to the queue")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                        .create_sub_option(|s| {
+                            s.name("name")
+                                .description("The name of the playlist")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                        })
+                })
+                .create_option(|o| {
+                    o.name("delete")
+                        .description("Deletes one of your playlists")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                        .create_sub_option(|s| {
+                            s.name("name")
+                                .description("The name of the playlist")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                        })
+                })
+                .create_option(|o| {
+                    o.name("save")
+                        .description("Saves the queue as a playlist, replacing the songs of an existing one")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                        .create_sub_option(|s| {
+                            s.name("name")
+                                .description("The name of the playlist")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                        })
+                        .create_sub_option(|s| {
+                            s.name("shared")
+                                .description("Lets everyone in the server play it")
+                                .kind(ApplicationCommandOptionType::Boolean)
+                                .required(false)
+                        })
+                })
+                .create_option(|o| {
+                    o.name("share")
+                        .description("Shares one of your playlists with the server, or makes it personal again")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                        .create_sub_option(|s| {
+                            s.name("name")
+                                .description("The name of the playlist")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                        })
+                })
+        })
+        .create_application_command(|c| {
+            c.name("grab")
+                .description("Sends you the currently playing song in a DM and adds it to your favourites")
+        })
+        .create_application_command(|c| {
+            c.name("favourites")
+                .description("Your favourite songs, use /play favourites to queue them")
+                .create_option(|o| {
+                    o.name("list")
+                        .description("Lists your favourite songs")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                })
+                .create_option(|o| {
+                    o.name("remove")
+                        .description("Removes a song from your favourites")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                        .create_sub_option(|s| {
+                            s.name("index")
+                                .description("The index of the song, use /favourites list to find it")
+                                .kind(ApplicationCommandOptionType::Integer)
+                                .required(true)
+                        })
+                })
+        })
+        .create_application_command(|c| {
+            c.name("library")
+                .description("The local music library of the bot")
+                .create_option(|o| {
+                    o.name("info")
+                        .description("Shows how many songs are in the library")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                })
+                .create_option(|o| {
+                    o.name("search")
+                        .description("Searches the library by title, artist or file name")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                        .create_sub_option(|s| {
+                            s.name("query")
+                                .description("The title, artist or file name")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                        })
+                })
+                .create_option(|o| {
+                    o.name("play")
+                        .description("Adds the best match from the library to the queue")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                        .create_sub_option(|s| {
+                            s.name("query")
+                                .description("The title, artist or file name")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                        })
+                })
+        })
+        .create_application_command(|c| {
+            c.name("station")
+                .description("Radio stations saved on this server")
+                .create_option(|o| {
+                    o.name("list")
+                        .description("Lists the saved radio stations")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                })
+                .create_option(|o| {
+                    o.name("play")
+                        .description("Adds a saved radio station to the queue")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                        .create_sub_option(|s| {
+                            s.name("name")
+                                .description("The name of the station")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                        })
+                })
+                .create_option(|o| {
+                    o.name("add")
+                        .description("Saves a radio station for everyone on this server")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                        .create_sub_option(|s| {
+                            s.name("name")
+                                .description("The name of the station")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                        })
+                        .create_sub_option(|s| {
+                            s.name("url")
+                                .description("The link to the stream")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                        })
+                })
+                .create_option(|o| {
+                    o.name("remove")
+                        .description("Removes a saved radio station")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                        .create_sub_option(|s| {
+                            s.name("name")
+                                .description("The name of the station")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                        })
+                })
+        })
+        .create_application_command(|c| {
+            c.name("nowplaying")
+                .description("Shows the currently playing track")
+        })
+        .create_application_command(|c| {
+            c.name("shuffle")
+                .description("Shuffles the queue without changing the currently playing song")
+        })
+        .create_application_command(|c| {
+            c.name("remove")
+                .description("Removes songs from the queue")
+                .create_option(|o| {
+                    o.name("songs")
+                        .description(
+                            "The index of the song, a range like 3-10, or a list like 2 5 7",
+                        )
+                        .kind(ApplicationCommandOptionType::String)
+                        .required(true)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("removedupes")
+                .description("Removes songs that are already in the queue")
+        })
+        .create_application_command(|c| {
+            c.name("removeuser")
+                .description("Removes every song a user added to the queue")
+                .create_option(|o| {
+                    o.name("user")
+                        .description("The user whose songs to remove")
+                        .kind(ApplicationCommandOptionType::User)
+                        .required(true)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("clear")
+                .description("Removes every upcoming song, but keeps the current one playing")
+        })
+        .create_application_command(|c| {
+            c.name("move")
+                .description("Moves a song to another spot in the queue")
+                .create_option(|o| {
+                    o.name("from")
+                        .description("The index of the song to move, use /queue to find it")
+                        .kind(ApplicationCommandOptionType::Integer)
+                        .required(true)
+                })
+                .create_option(|o| {
+                    o.name("to")
+                        .description("The index to move the song to")
+                        .kind(ApplicationCommandOptionType::Integer)
+                        .required(true)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("swap")
+                .description("Swaps two songs in the queue")
+                .create_option(|o| {
+                    o.name("first")
+                        .description("The index of the first song, use /queue to find it")
+                        .kind(ApplicationCommandOptionType::Integer)
+                        .required(true)
+                })
+                .create_option(|o| {
+                    o.name("second")
+                        .description("The index of the second song")
+                        .kind(ApplicationCommandOptionType::Integer)
+                        .required(true)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("filter")
+                .description("Shows the active audio filters, or turns one on or off")
+                .create_option(|o| {
+                    o.name("name")
+                        .description("The filter to turn on or off")
+                        .kind(ApplicationCommandOptionType::String)
+                        .required(false)
+                        .add_string_choice("Bass boost", "bassboost")
+                        .add_string_choice("Nightcore", "nightcore")
+                        .add_string_choice("Vaporwave", "vaporwave")
+                        .add_string_choice("Karaoke", "karaoke")
+                        .add_string_choice("Tremolo", "tremolo")
+                        .add_string_choice("8D", "8d")
+                        .add_string_choice("Tempo", "tempo")
+                        .add_string_choice("Pitch", "pitch")
+                        .add_string_choice("Off", "off")
+                })
+                .create_option(|o| {
+                    o.name("value")
+                        .description("The factor for tempo and pitch, from 0.5 to 2")
+                        .kind(ApplicationCommandOptionType::String)
+                        .required(false)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("volume")
+                .description("Shows or sets the volume of the current track")
+                .create_option(|o| {
+                    o.name("volume")
+                        .description("A number from 0 to 100")
+                        .kind(ApplicationCommandOptionType::Integer)
+                        .required(false)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("join")
+                .description("Makes the bot join the voice channel you are in")
+        })
+        .create_application_command(|c| c.name("mute").description("Mutes the bot"))
+        .create_application_command(|c| {
+            c.name("lyrics")
+                .description("Shows the lyrics to a song, or the currently playing song")
+                .create_option(|o| {
+                    o.name("song")
+                        .description("The name of the song")
+                        .kind(ApplicationCommandOptionType::String)
+                        .required(false)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("djonly")
+                .description("Enables/Disables dj only mode")
+        })
+        .create_application_command(|c| {
+            c.name("fairqueue")
+                .description("Enables/Disables fair queue mode, upcoming songs take turns between requesters")
+        })
+        .create_application_command(|c| {
+            c.name("normalise")
+                .description("Enables/Disables loudness normalisation, songs play at about the same volume")
+        })
+        .create_application_command(|c| {
+            c.name("crossfade")
+                .description("Shows or changes how many seconds songs fade into each other")
+                .create_option(|o| {
+                    o.name("seconds")
+                        .description("How long the fade is, 0 turns it off")
+                        .kind(ApplicationCommandOptionType::Integer)
+                        .required(false)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("queuelimits")
+                .description("Shows or changes the limits on what users can add to the queue")
+                .create_option(|o| {
+                    o.name("limit")
+                        .description("The limit to change")
+                        .kind(ApplicationCommandOptionType::String)
+                        .required(false)
+                        .add_string_choice("Songs per user", "user")
+                        .add_string_choice("Songs in the queue", "queue")
+                        .add_string_choice("Song length", "length")
+                        .add_string_choice("Songs per playlist", "playlist")
+                        .add_string_choice("DJs and admins exempt", "exempt")
+                })
+                .create_option(|o| {
+                    o.name("value")
+                        .description("A number, a length like 10:00, on, or off to remove the limit")
+                        .kind(ApplicationCommandOptionType::String)
+                        .required(false)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("skipthreshold")
+                .description("Shows or changes how many listeners have to vote to skip a song")
+                .create_option(|o| {
+                    o.name("threshold")
+                        .description("A number of votes, or a percentage of listeners like 50%")
+                        .kind(ApplicationCommandOptionType::String)
+                        .required(false)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("perms")
+                .description("Shows or changes the permissions of members")
+                .create_option(|o| {
+                    o.name("list")
+                        .description("Lists the users with the selected perm")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                        .create_sub_option(|s| {
+                            s.name("level")
+                                .description("The permission level")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                                .add_string_choice("Admin", "admin")
+                                .add_string_choice("DJ", "dj")
+                                .add_string_choice("Blacklist", "blacklist")
+                        })
+                })
+                .create_option(|o| {
+                    o.name("set")
+                        .description("Sets a users permission to the selected perm")
+                        .kind(ApplicationCommandOptionType::SubCommand)
+                        .create_sub_option(|s| {
+                            s.name("user")
+                                .description("The user to change the permission of")
+                                .kind(ApplicationCommandOptionType::User)
+                                .required(true)
+                        })
+                        .create_sub_option(|s| {
+                            s.name("level")
+                                .description("The permission level")
+                                .kind(ApplicationCommandOptionType::String)
+                                .required(true)
+                                .add_string_choice("Admin", "admin")
+                                .add_string_choice("DJ", "dj")
+                                .add_string_choice("User", "user")
+                                .add_string_choice("Blacklist", "blacklist")
+                        })
+                })
+        })
+}
+
+pub async fn register_guild_commands(
+    ctx: &Context,
+    guild_id: GuildId,
+) -> serenity::Result<Vec<ApplicationCommand>> {
+    guild_id
+        .set_application_commands(ctx, create_commands)
+        .await
+}
+
+pub async fn register_global_commands(ctx: &Context) -> serenity::Result<Vec<ApplicationCommand>> {
+    ApplicationCommand::set_global_application_commands(ctx, create_commands).await
+}
+
+pub async fn clear_commands(ctx: &Context, guild_id: GuildId) -> serenity::Result<()> {
+    guild_id.set_application_commands(ctx, |c| c).await?;
+    ApplicationCommand::set_global_application_commands(ctx, |c| c).await?;
+
+    Ok(())
+}
+
+fn find_option<'a>(
+    options: &'a [ApplicationCommandInteractionDataOption],
+    name: &str,
+) -> Option<&'a ApplicationCommandInteractionDataOptionValue> {
+    options
+        .iter()
+        .find(|option| option.name == name)
+        .and_then(|option| option.resolved.as_ref())
+}
+
+fn string_option(
+    options: &[ApplicationCommandInteractionDataOption],
+    name: &str,
+) -> Option<String> {
+    match find_option(options, name) {
+        Some(ApplicationCommandInteractionDataOptionValue::String(value)) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+fn integer_option(options: &[ApplicationCommandInteractionDataOption], name: &str) -> Option<i64> {
+    match find_option(options, name) {
+        Some(ApplicationCommandInteractionDataOptionValue::Integer(value)) => Some(*value),
+        _ => None,
+    }
+}
+
+fn boolean_option(options: &[ApplicationCommandInteractionDataOption], name: &str) -> Option<bool> {
+    match find_option(options, name) {
+        Some(ApplicationCommandInteractionDataOptionValue::Boolean(value)) => Some(*value),
+        _ => None,
+    }
+}
+
+fn user_option(options: &[ApplicationCommandInteractionDataOption], name: &str) -> Option<User> {
+    match find_option(options, name) {
+        Some(ApplicationCommandInteractionDataOptionValue::User(user, _)) => Some(user.clone()),
+        _ => None,
+    }
+}
+
+async fn run_check(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    command: &ApplicationCommandInteraction,
+) -> Result<(), Reason> {
+    let guild_id = invoker.guild_id();
+    let author_id = invoker.author_id();
+
+    let sub_command = command
+        .data
+        .options
+        .first()
+        .map(|sub_command| sub_command.name.as_str());
+
+    match (command.data.name.as_str(), sub_command) {
+        ("playlist" | "library" | "station", Some("play")) => {
+            check_dj_only(ctx, guild_id, author_id).await
+        }
+        ("station", Some("add" | "remove")) => check_admin_only(ctx, guild_id, author_id).await,
+        (
+            "queue" | "history" | "nowplaying" | "lyrics" | "voteskip" | "playlist" | "grab"
+            | "favourites" | "library" | "station",
+            _,
+        ) => check_not_blacklisted(ctx, guild_id, author_id).await,
+        (
+            "perms" | "djonly" | "fairqueue" | "normalise" | "crossfade" | "queuelimits"
+            | "skipthreshold",
+            _,
+        ) => check_admin_only(ctx, guild_id, author_id).await,
+        _ => check_dj_only(ctx, guild_id, author_id).await,
+    }
+}
+
+async fn run_command(
+    ctx: &Context,
+    invoker: Invoker<'_>,
+    command: &ApplicationCommandInteraction,
+) -> CommandResult {
+    let options = &command.data.options;
+
+    match command.data.name.as_str() {
+        "play" => play_track(ctx, invoker, string_option(options, "song")).await,
+        "playnext" => play_track_next(ctx, invoker, string_option(options, "song")).await,
+        "search" => search_tracks(ctx, invoker, string_option(options, "query")).await,
+        "skip" => skip_track(ctx, invoker).await,
+        "skipto" => {
+            let index = integer_option(options, "index").unwrap_or_default();
+            skip_to_track(ctx, invoker, index.max(0) as usize).await
+        }
+        "voteskip" => vote_skip(ctx, invoker).await,
+        "stop" => stop_playing(ctx, invoker).await,
+        "pause" => toggle_pause(ctx, invoker).await,
+        "resume" => resume_track(ctx, invoker).await,
+        "restart" => restart_track(ctx, invoker).await,
+        "seek" => seek_to(ctx, invoker, string_option(options, "position")).await,
+        "forward" => {
+            let seconds = integer_option(options, "seconds").map(|seconds| seconds.max(0) as u64);
+            seek_forward(ctx, invoker, seconds).await
+        }
+        "rewind" => {
+            let seconds = integer_option(options, "seconds").map(|seconds| seconds.max(0) as u64);
+            seek_backward(ctx, invoker, seconds).await
+        }
+        "loop" => {
+            let repeat_mode = match integer_option(options, "times") {
+                Some(times) if times > 0 => Some(RepeatMode::TrackTimes(times as usize)),
+                _ => string_option(options, "mode").and_then(|mode| mode.parse().ok()),
+            };
+            set_repeat_mode(ctx, invoker, repeat_mode).await
+        }
+        "autoplay" => toggle_autoplay(ctx, invoker).await,
+        "queue" => {
+            let page = integer_option(options, "page").map(|page| page.max(1) as usize);
+            show_queue(ctx, invoker, page).await
+        }
+        "history" => show_history(ctx, invoker).await,
+        "previous" => play_previous(ctx, invoker).await,
+        "replay" => {
+            let index = integer_option(options, "index").unwrap_or_default();
+            replay_track(ctx, invoker, index.max(0) as usize).await
+        }
+        "playlist" => {
+            let sub_command = match options.first() {
+                Some(sub_command) => sub_command,
+                None => return Ok(()),
+            };
+
+            let options = &sub_command.options;
+            let name = string_option(options, "name").unwrap_or_default();
+            let shared = boolean_option(options, "shared").unwrap_or_default();
+
+            match sub_command.name.as_str() {
+                "list" => show_playlist(ctx, invoker, None).await,
+                "create" => create_user_playlist(ctx, invoker, &name, shared).await,
+                "add" => add_to_playlist(ctx, invoker, &name, string_option(options, "song")).await,
+                "remove" => {
+                    let index = integer_option(options, "index").unwrap_or_default();
+                    remove_from_playlist(ctx, invoker, &name, index.max(0) as usize).await
+                }
+                "show" => show_playlist(ctx, invoker, Some(name)).await,
+                "play" => play_playlist(ctx, invoker, &name).await,
+                "delete" => delete_user_playlist(ctx, invoker, &name).await,
+                "save" => save_queue_as_playlist(ctx, invoker, &name, shared).await,
+                "share" => toggle_playlist_shared(ctx, invoker, &name).await,
+                _ => Ok(()),
+            }
+        }
+        "grab" => grab_track(ctx, invoker).await,
+        "favourites" => {
+            let sub_command = match options.first() {
+                Some(sub_command) => sub_command,
+                None => return Ok(()),
+            };
+
+            match sub_command.name.as_str() {
+                "list" => show_favourites(ctx, invoker).await,
+                "remove" => {
+                    let index = integer_option(&sub_command.options, "index").unwrap_or_default();
+                    remove_from_favourites(ctx, invoker, index.max(0) as usize).await
+                }
+                _ => Ok(()),
+            }
+        }
+        "library" => {
+            let sub_command = match options.first() {
+                Some(sub_command) => sub_command,
+                None => return Ok(()),
+            };
+
+            let query = string_option(&sub_command.options, "query");
+
+            match sub_command.name.as_str() {
+                "info" => show_library(ctx, invoker).await,
+                "search" => search_library_tracks(ctx, invoker, query).await,
+                "play" => play_library_track(ctx, invoker, query).await,
+                _ => Ok(()),
+            }
+        }
+        "station" => {
+            let sub_command = match options.first() {
+                Some(sub_command) => sub_command,
+                None => return Ok(()),
+            };
+
+            let name = string_option(&sub_command.options, "name").unwrap_or_default();
+
+            match sub_command.name.as_str() {
+                "list" => show_stations(ctx, invoker).await,
+                "play" => play_station(ctx, invoker, &name).await,
+                "add" => {
+                    let url = string_option(&sub_command.options, "url").unwrap_or_default();
+                    add_station(ctx, invoker, name.trim(), url.trim()).await
+                }
+                "remove" => remove_station(ctx, invoker, &name).await,
+                _ => Ok(()),
+            }
+        }
+        "nowplaying" => show_now_playing(ctx, invoker).await,
+        "shuffle" => shuffle_tracks(ctx, invoker).await,
+        "remove" => {
+            let songs = string_option(options, "songs").unwrap_or_default();
+            remove_tracks(ctx, invoker, &songs).await
+        }
+        "removedupes" => remove_duplicate_tracks(ctx, invoker).await,
+        "removeuser" => match user_option(options, "user") {
+            Some(user) => remove_user_tracks(ctx, invoker, user.id).await,
+            None => {
+                invoker.reply(ctx, "Not a valid user").await?;
+                Ok(())
+            }
+        },
+        "clear" => clear_queue(ctx, invoker).await,
+        "move" => {
+            let from = integer_option(options, "from").unwrap_or_default();
+            let to = integer_option(options, "to").unwrap_or_default();
+            move_track(ctx, invoker, from.max(0) as usize, to.max(0) as usize).await
+        }
+        "swap" => {
+            let first = integer_option(options, "first").unwrap_or_default();
+            let second = integer_option(options, "second").unwrap_or_default();
+            swap_tracks(ctx, invoker, first.max(0) as usize, second.max(0) as usize).await
+        }
+        "filter" => {
+            let name = string_option(options, "name");
+            let value = string_option(options, "value");
+            change_filter(ctx, invoker, name, value).await
+        }
+        "volume" => {
+            let volume = integer_option(options, "volume").map(|volume| volume as i32);
+            set_volume(ctx, invoker, volume).await
+        }
+        "join" => join_channel(ctx, invoker).await,
+        "mute" => toggle_mute(ctx, invoker).await,
+        "lyrics" => show_lyrics(ctx, invoker, string_option(options, "song")).await,
+        "djonly" => toggle_dj_only(ctx, invoker).await,
+        "fairqueue" => toggle_fair_queue(ctx, invoker).await,
+        "normalise" => toggle_normalise(ctx, invoker).await,
+        "crossfade" => {
+            let seconds = integer_option(options, "seconds").map(|seconds| seconds.max(0) as u64);
+            change_crossfade(ctx, invoker, seconds).await
+        }
+        "queuelimits" => {
+            let limit = string_option(options, "limit");
+            let value = string_option(options, "value");
+            change_queue_limits(ctx, invoker, limit, value).await
+        }
+        "skipthreshold" => {
+            change_skip_threshold(ctx, invoker, string_option(options, "threshold")).await
+        }
+        "perms" => {
+            let sub_command = match options.first() {
+                Some(sub_command) => sub_command,
+                None => return Ok(()),
+            };
+
+            let level = string_option(&sub_command.options, "level");
+
+            match sub_command.name.as_str() {
+                "list" => list_perms(ctx, invoker, level).await,
+                "set" => match user_option(&sub_command.options, "user") {
+                    Some(user) => set_perms(ctx, invoker, user, level).await,
+                    None => {
+                        invoker.reply(ctx, "Not a valid user").await?;
+                        Ok(())
+                    }
+                },
+                _ => Ok(()),
+            }
+        }
+        name => {
+            warn!("Unknown slash command {}", name);
+            invoker.say(ctx, "Unknown command").await?;
+            Ok(())
+        }
+    }
+}
+
+pub async fn handle_slash_command(ctx: &Context, command: &ApplicationCommandInteraction) {
+    if command.guild_id.is_none() {
+        let _ = command
+            .create_interaction_response(ctx, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|d| {
+                        d.content("Commands can only be used in a server")
+                    })
+            })
+            .await;
+        return;
+    }
+
+    if let Err(e) = command
+        .create_interaction_response(ctx, |r| {
+            r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+        })
+        .await
+    {
+        warn!(
+            "Could not respond to slash command {}, {:?}",
+            command.data.name, e
+        );
+        return;
+    }
+
+    let invoker = Invoker::from(command);
+
+    if let Err(reason) = run_check(ctx, invoker, command).await {
+        let content = match reason {
+            Reason::User(reason) => reason,
+            reason => {
+                info!("{:?}", reason);
+                INSUFFICIENT_PERMISSIONS_MESSAGE.to_string()
+            }
+        };
+
+        let _ = invoker.say(ctx, content).await;
+        return;
+    }
+
+    if let Err(e) = run_command(ctx, invoker, command).await {
+        warn!("Error with slash command {}, {:?}", command.data.name, e);
+        let _ = invoker.say(ctx, format!("Command returned an error, {e:?}, please report this on the support server https://discord.gg/5YytF9fPHr")).await;
+    }
+}
@@ -6,31 +6,36 @@ use std::sync::{
     Arc,
 };
 
-use crate::queue::Queue;
+use tracing::warn;
+
+use crate::{now_playing_panel::update_panel, queue::Queue};
 
 pub struct TrackStartNotifier {
     pub chan_id: ChannelId,
     pub http: Arc<Http>,
+    pub queue: Queue,
 }
 
 #[async_trait]
 impl VoiceEventHandler for TrackStartNotifier {
     async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
-        if let EventContext::Track(&[(_, handle)]) = ctx {
-            let metadata = handle.metadata();
-            let title = metadata.title.clone().unwrap_or_default();
-            let url = metadata.source_url.clone().unwrap_or_default();
-            let _ = self
-                .chan_id
-                .send_message(&self.http, |m| {
-                    m.embed(|e| {
-                        e.title("Now playing");
-                        e.description(format!("[{}]({})", title, url));
+        if let EventContext::Track(&[(state, handle)]) = ctx {
+            // A track fading out still fires its events after the next one took over the panel
+            let current_uuid = self
+                .queue
+                .current()
+                .lock()
+                .as_ref()
+                .map(|current| current.uuid());
 
-                        e
-                    })
-                })
-                .await;
+            if current_uuid != Some(handle.uuid()) {
+                return None;
+            }
+
+            if let Err(e) = update_panel(&self.http, &self.queue, self.chan_id, handle, state).await
+            {
+                warn!("Could not update the now playing panel, {:?}", e);
+            }
         }
 
         None